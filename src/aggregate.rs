@@ -0,0 +1,703 @@
+use std::{
+    collections::BTreeMap,
+    mem,
+    ops::{Add, Sub},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_signals::{
+    signal::Signal,
+    signal_vec::{SignalVec, VecDiff},
+};
+use pin_project_lite::pin_project;
+
+use crate::sequence::Sequence;
+
+/// Incrementally maintained aggregate of `SignalVec` items, see `Aggregate`.
+pub trait Aggregator<A> {
+    type Output;
+
+    fn insert(&mut self, index: usize, item: A);
+
+    fn update(&mut self, index: usize, item: A) {
+        self.remove(index);
+        self.insert(index, item);
+    }
+
+    fn remove(&mut self, index: usize);
+
+    fn move_item(&mut self, old_index: usize, new_index: usize);
+
+    fn clear(&mut self);
+
+    fn replace(&mut self, items: Vec<A>) {
+        self.clear();
+        for (index, item) in items.into_iter().enumerate() {
+            self.insert(index, item);
+        }
+    }
+
+    /// Returns the aggregated value if it changed since the last call, and always
+    /// on the first call.
+    fn changed(&mut self) -> Option<Self::Output>;
+}
+
+// Keeps the last emitted value, to emit only if the value changes.
+#[derive(Debug)]
+pub(crate) struct Emitted<T>(Option<T>);
+
+impl<T> Default for Emitted<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<T: PartialEq + Clone> Emitted<T> {
+    pub(crate) fn emit(&mut self, value: T) -> Option<T> {
+        if self.0.as_ref() == Some(&value) {
+            None
+        } else {
+            self.0 = Some(value.clone());
+            Some(value)
+        }
+    }
+}
+
+pin_project! {
+    #[derive(Debug)]
+    #[must_use = "Signals do nothing unless polled"]
+    pub struct Aggregate<S, G> {
+        #[pin]
+        signal: Option<S>,
+        aggregator: G,
+        len: usize,
+    }
+}
+
+impl<S, G> Aggregate<S, G> {
+    pub(crate) fn new(signal: S, aggregator: G) -> Self {
+        Self {
+            signal: Some(signal),
+            aggregator,
+            len: 0,
+        }
+    }
+
+    pub(crate) fn aggregator_mut(self: Pin<&mut Self>) -> &mut G {
+        self.project().aggregator
+    }
+}
+
+impl<S, G> Signal for Aggregate<S, G>
+where
+    S: SignalVec,
+    G: Aggregator<S::Item>,
+{
+    type Item = G::Output;
+
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        let done = loop {
+            break match this
+                .signal
+                .as_mut()
+                .as_pin_mut()
+                .map(|signal| signal.poll_vec_change(cx))
+            {
+                Some(Poll::Ready(Some(diff))) => {
+                    match diff {
+                        VecDiff::Replace { values } => {
+                            *this.len = values.len();
+                            this.aggregator.replace(values);
+                        }
+                        VecDiff::InsertAt { index, value } => {
+                            *this.len += 1;
+                            this.aggregator.insert(index, value);
+                        }
+                        VecDiff::UpdateAt { index, value } => {
+                            this.aggregator.update(index, value);
+                        }
+                        VecDiff::RemoveAt { index } => {
+                            *this.len -= 1;
+                            this.aggregator.remove(index);
+                        }
+                        VecDiff::Move {
+                            old_index,
+                            new_index,
+                        } => {
+                            this.aggregator.move_item(old_index, new_index);
+                        }
+                        VecDiff::Push { value } => {
+                            this.aggregator.insert(*this.len, value);
+                            *this.len += 1;
+                        }
+                        VecDiff::Pop {} => {
+                            *this.len -= 1;
+                            this.aggregator.remove(*this.len);
+                        }
+                        VecDiff::Clear {} => {
+                            *this.len = 0;
+                            this.aggregator.clear();
+                        }
+                    }
+                    continue;
+                }
+                Some(Poll::Ready(None)) => {
+                    this.signal.set(None);
+                    true
+                }
+                Some(Poll::Pending) => false,
+                None => true,
+            };
+        };
+
+        match this.aggregator.changed() {
+            Some(output) => Poll::Ready(Some(output)),
+            None if done => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SumBy<F, T> {
+    f: F,
+    values: Sequence<T>,
+    sum: T,
+    emitted: Emitted<T>,
+}
+
+impl<F, T: Default> SumBy<F, T> {
+    pub(crate) fn new(f: F) -> Self {
+        Self {
+            f,
+            values: Sequence::default(),
+            sum: T::default(),
+            emitted: Emitted::default(),
+        }
+    }
+}
+
+impl<A, F, T> Aggregator<A> for SumBy<F, T>
+where
+    F: FnMut(&A) -> T,
+    T: Copy + Default + PartialEq + Add<Output = T> + Sub<Output = T>,
+{
+    type Output = T;
+
+    fn insert(&mut self, index: usize, item: A) {
+        let value = (self.f)(&item);
+        self.sum = self.sum + value;
+        self.values.insert(index, value, 0);
+    }
+
+    fn update(&mut self, index: usize, item: A) {
+        let value = (self.f)(&item);
+        let old = mem::replace(self.values.get_mut(index), value);
+        self.sum = self.sum - old + value;
+    }
+
+    fn remove(&mut self, index: usize) {
+        self.sum = self.sum - self.values.remove(index);
+    }
+
+    fn move_item(&mut self, old_index: usize, new_index: usize) {
+        self.values.move_item(old_index, new_index);
+    }
+
+    // also on Replace, so the sum is computed from scratch, without the rounding
+    // errors accumulated by float values
+    fn clear(&mut self) {
+        self.values.clear();
+        self.sum = T::default();
+    }
+
+    fn changed(&mut self) -> Option<T> {
+        self.emitted.emit(self.sum)
+    }
+}
+
+#[derive(Debug)]
+pub struct CountWhere<F> {
+    f: F,
+    // weight 1 if the item matches
+    matches: Sequence<()>,
+    emitted: Emitted<usize>,
+}
+
+impl<F> CountWhere<F> {
+    pub(crate) fn new(f: F) -> Self {
+        Self {
+            f,
+            matches: Sequence::default(),
+            emitted: Emitted::default(),
+        }
+    }
+}
+
+impl<A, F> Aggregator<A> for CountWhere<F>
+where
+    F: FnMut(&A) -> bool,
+{
+    type Output = usize;
+
+    fn insert(&mut self, index: usize, item: A) {
+        let matched = (self.f)(&item);
+        self.matches.insert(index, (), matched as usize);
+    }
+
+    fn update(&mut self, index: usize, item: A) {
+        let matched = (self.f)(&item);
+        self.matches.set_weight(index, matched as usize);
+    }
+
+    fn remove(&mut self, index: usize) {
+        self.matches.remove(index);
+    }
+
+    fn move_item(&mut self, old_index: usize, new_index: usize) {
+        self.matches.move_item(old_index, new_index);
+    }
+
+    fn clear(&mut self) {
+        self.matches.clear();
+    }
+
+    fn changed(&mut self) -> Option<usize> {
+        self.emitted.emit(self.matches.total())
+    }
+}
+
+#[derive(Debug)]
+pub struct FoldGroup<A, T, F, R> {
+    init: T,
+    acc: T,
+    add: F,
+    remove: R,
+    items: Sequence<A>,
+    emitted: Emitted<T>,
+}
+
+impl<A, T: Clone, F, R> FoldGroup<A, T, F, R> {
+    pub(crate) fn new(init: T, add: F, remove: R) -> Self {
+        Self {
+            acc: init.clone(),
+            init,
+            add,
+            remove,
+            items: Sequence::default(),
+            emitted: Emitted::default(),
+        }
+    }
+}
+
+impl<A, T, F, R> Aggregator<A> for FoldGroup<A, T, F, R>
+where
+    T: Clone + PartialEq,
+    F: FnMut(&mut T, &A),
+    R: FnMut(&mut T, &A),
+{
+    type Output = T;
+
+    fn insert(&mut self, index: usize, item: A) {
+        (self.add)(&mut self.acc, &item);
+        self.items.insert(index, item, 0);
+    }
+
+    fn update(&mut self, index: usize, item: A) {
+        (self.add)(&mut self.acc, &item);
+        let old = mem::replace(self.items.get_mut(index), item);
+        (self.remove)(&mut self.acc, &old);
+    }
+
+    fn remove(&mut self, index: usize) {
+        let item = self.items.remove(index);
+        (self.remove)(&mut self.acc, &item);
+    }
+
+    fn move_item(&mut self, old_index: usize, new_index: usize) {
+        self.items.move_item(old_index, new_index);
+    }
+
+    fn clear(&mut self) {
+        self.items.clear();
+        self.acc = self.init.clone();
+    }
+
+    fn changed(&mut self) -> Option<T> {
+        self.emitted.emit(self.acc.clone())
+    }
+}
+
+/// Keeps the count of matching items, each diff is O(log n). Only the match of
+/// every position is remembered, to know what a removed or updated item counted.
+#[derive(Debug)]
+pub struct Matches<F> {
+    count: CountWhere<F>,
+    all: bool,
+    emitted: Emitted<bool>,
+}
+
+impl<A, F> Aggregator<A> for Matches<F>
+where
+    F: FnMut(&A) -> bool,
+{
+    type Output = bool;
+
+    fn insert(&mut self, index: usize, item: A) {
+        self.count.insert(index, item);
+    }
+
+    fn update(&mut self, index: usize, item: A) {
+        self.count.update(index, item);
+    }
+
+    fn remove(&mut self, index: usize) {
+        Aggregator::<A>::remove(&mut self.count, index);
+    }
+
+    fn move_item(&mut self, old_index: usize, new_index: usize) {
+        Aggregator::<A>::move_item(&mut self.count, old_index, new_index);
+    }
+
+    fn clear(&mut self) {
+        Aggregator::<A>::clear(&mut self.count);
+    }
+
+    fn changed(&mut self) -> Option<bool> {
+        let matches = &self.count.matches;
+        self.emitted.emit(if self.all {
+            matches.total() == matches.len()
+        } else {
+            matches.total() > 0
+        })
+    }
+}
+
+pin_project! {
+    /// Emits only when the result flips.
+    #[derive(Debug)]
+    #[must_use = "Signals do nothing unless polled"]
+    pub struct All<S, F> {
+        #[pin]
+        aggregate: Aggregate<S, Matches<F>>,
+    }
+}
+
+impl<S, F> All<S, F> {
+    pub(crate) fn new(signal: S, f: F) -> Self {
+        Self {
+            aggregate: Aggregate::new(
+                signal,
+                Matches {
+                    count: CountWhere::new(f),
+                    all: true,
+                    emitted: Emitted::default(),
+                },
+            ),
+        }
+    }
+}
+
+impl<S, F> Signal for All<S, F>
+where
+    S: SignalVec,
+    F: FnMut(&S::Item) -> bool,
+{
+    type Item = bool;
+
+    #[inline]
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        self.project().aggregate.poll_change(cx)
+    }
+}
+
+pin_project! {
+    /// Emits only when the result flips.
+    #[derive(Debug)]
+    #[must_use = "Signals do nothing unless polled"]
+    pub struct Any<S, F> {
+        #[pin]
+        aggregate: Aggregate<S, Matches<F>>,
+    }
+}
+
+impl<S, F> Any<S, F> {
+    pub(crate) fn new(signal: S, f: F) -> Self {
+        Self {
+            aggregate: Aggregate::new(
+                signal,
+                Matches {
+                    count: CountWhere::new(f),
+                    all: false,
+                    emitted: Emitted::default(),
+                },
+            ),
+        }
+    }
+}
+
+impl<S, F> Signal for Any<S, F>
+where
+    S: SignalVec,
+    F: FnMut(&S::Item) -> bool,
+{
+    type Item = bool;
+
+    #[inline]
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        self.project().aggregate.poll_change(cx)
+    }
+}
+
+/// Items are kept ordered by key, ties are resolved by the order of insertion.
+#[derive(Debug)]
+pub struct MinMaxByKey<A, F, K> {
+    f: F,
+    max: bool,
+    items: Sequence<((K, u64), A)>,
+    // handles of items ordered by key
+    ordered: BTreeMap<(K, u64), usize>,
+    next: u64,
+    emitted: Emitted<Option<(K, u64)>>,
+}
+
+impl<A, F, K> MinMaxByKey<A, F, K> {
+    pub(crate) fn new(f: F, max: bool) -> Self {
+        Self {
+            f,
+            max,
+            items: Sequence::default(),
+            ordered: BTreeMap::new(),
+            next: 0,
+            emitted: Emitted::default(),
+        }
+    }
+}
+
+impl<A, F, K> Aggregator<A> for MinMaxByKey<A, F, K>
+where
+    A: Clone,
+    F: FnMut(&A) -> K,
+    K: Ord + Clone,
+{
+    type Output = Option<A>;
+
+    fn insert(&mut self, index: usize, item: A) {
+        let key = ((self.f)(&item), self.next);
+        self.next += 1;
+        let handle = self.items.insert(index, (key.clone(), item), 0);
+        self.ordered.insert(key, handle);
+    }
+
+    fn remove(&mut self, index: usize) {
+        let (key, _) = self.items.remove(index);
+        self.ordered.remove(&key);
+    }
+
+    fn move_item(&mut self, old_index: usize, new_index: usize) {
+        self.items.move_item(old_index, new_index);
+    }
+
+    fn clear(&mut self) {
+        self.items.clear();
+        self.ordered.clear();
+    }
+
+    fn changed(&mut self) -> Option<Option<A>> {
+        let found = if self.max {
+            self.ordered.last_key_value()
+        } else {
+            self.ordered.first_key_value()
+        };
+        self.emitted
+            .emit(found.map(|(key, _)| key.clone()))
+            .map(|_| found.map(|(_, handle)| self.items.value(*handle).1.clone()))
+    }
+}
+
+// Neumaier summation, keeps the rounding error low over long runs of changes.
+#[derive(Debug, Default)]
+struct CompensatedSum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl CompensatedSum {
+    fn add(&mut self, value: f64) {
+        let sum = self.sum + value;
+        self.compensation += if self.sum.abs() >= value.abs() {
+            (self.sum - sum) + value
+        } else {
+            (value - sum) + self.sum
+        };
+        self.sum = sum;
+    }
+
+    fn value(&self) -> f64 {
+        self.sum + self.compensation
+    }
+}
+
+#[derive(Debug)]
+pub struct AverageBy<F> {
+    f: F,
+    values: Sequence<f64>,
+    sum: CompensatedSum,
+    emitted: Emitted<Option<f64>>,
+}
+
+impl<F> AverageBy<F> {
+    pub(crate) fn new(f: F) -> Self {
+        Self {
+            f,
+            values: Sequence::default(),
+            sum: CompensatedSum::default(),
+            emitted: Emitted::default(),
+        }
+    }
+}
+
+impl<A, F> Aggregator<A> for AverageBy<F>
+where
+    F: FnMut(&A) -> f64,
+{
+    type Output = Option<f64>;
+
+    fn insert(&mut self, index: usize, item: A) {
+        let value = (self.f)(&item);
+        self.sum.add(value);
+        self.values.insert(index, value, 0);
+    }
+
+    fn update(&mut self, index: usize, item: A) {
+        let value = (self.f)(&item);
+        let old = mem::replace(self.values.get_mut(index), value);
+        self.sum.add(-old);
+        self.sum.add(value);
+    }
+
+    fn remove(&mut self, index: usize) {
+        self.sum.add(-self.values.remove(index));
+    }
+
+    fn move_item(&mut self, old_index: usize, new_index: usize) {
+        self.values.move_item(old_index, new_index);
+    }
+
+    // also on Replace, so the sum is computed from scratch
+    fn clear(&mut self) {
+        self.values.clear();
+        self.sum = CompensatedSum::default();
+    }
+
+    fn changed(&mut self) -> Option<Option<f64>> {
+        let len = self.values.len();
+        self.emitted
+            .emit((len > 0).then(|| self.sum.value() / len as f64))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::pin::pin;
+
+    use futures_signals::signal_vec::MutableVec;
+
+    use crate::{SignalVecFinalizerExt, testing::poll_signal};
+
+    #[test]
+    fn aggregates() {
+        let vec = MutableVec::new_with_values(vec![3, 1, 4]);
+        let mut sum = pin!(vec.signal_vec().sum_by(|item| *item));
+        let mut max = pin!(vec.signal_vec().max_by_key(|item| *item));
+        let mut average = pin!(vec.signal_vec().average_by(|item| *item as f64));
+        assert_eq!(poll_signal(sum.as_mut()), vec![8]);
+        assert_eq!(poll_signal(max.as_mut()), vec![Some(4)]);
+        assert_eq!(poll_signal(average.as_mut()), vec![Some(8.0 / 3.0)]);
+
+        vec.lock_mut().set(1, 5);
+        vec.lock_mut().move_from_to(0, 2);
+        assert_eq!(poll_signal(sum.as_mut()), vec![12]);
+        assert_eq!(poll_signal(max.as_mut()), vec![Some(5)]);
+
+        vec.lock_mut().remove(1);
+        vec.lock_mut().push(1);
+        assert_eq!(poll_signal(sum.as_mut()), vec![9]);
+        assert_eq!(poll_signal(max.as_mut()), vec![]);
+
+        vec.lock_mut().clear();
+        assert_eq!(poll_signal(sum.as_mut()), vec![0]);
+        assert_eq!(poll_signal(max.as_mut()), vec![None]);
+        assert_eq!(poll_signal(average.as_mut()), vec![None]);
+    }
+
+    #[test]
+    fn average_rounding() {
+        let vec = MutableVec::new_with_values(vec![1e17, 1.0]);
+        let mut average = pin!(vec.signal_vec().average_by(|item| *item));
+        assert_eq!(poll_signal(average.as_mut()), vec![Some(5e16)]);
+
+        vec.lock_mut().remove(0);
+        assert_eq!(poll_signal(average.as_mut()), vec![Some(1.0)]);
+    }
+
+    #[test]
+    fn fold_group() {
+        let vec = MutableVec::new_with_values(vec![1usize, 2, 1]);
+        let mut histogram = pin!(vec.signal_vec().fold_group(
+            [0; 3],
+            |acc, item| acc[*item] += 1,
+            |acc, item| acc[*item] -= 1,
+        ));
+        assert_eq!(poll_signal(histogram.as_mut()), vec![[0, 2, 1]]);
+
+        vec.lock_mut().set(1, 0);
+        vec.lock_mut().push(2);
+        assert_eq!(poll_signal(histogram.as_mut()), vec![[1, 2, 1]]);
+
+        vec.lock_mut().move_from_to(0, 2);
+        assert!(poll_signal(histogram.as_mut()).is_empty());
+
+        vec.lock_mut().remove(0);
+        assert_eq!(poll_signal(histogram.as_mut()), vec![[0, 2, 1]]);
+    }
+
+    #[test]
+    fn all_any() {
+        let vec = MutableVec::new_with_values(vec![2, 4]);
+        let mut all = pin!(vec.signal_vec().all(|item| item % 2 == 0));
+        let mut any = pin!(vec.signal_vec().any(|item| item % 2 == 1));
+        assert_eq!(poll_signal(all.as_mut()), vec![true]);
+        assert_eq!(poll_signal(any.as_mut()), vec![false]);
+
+        vec.lock_mut().push(6);
+        vec.lock_mut().set(0, 3);
+        assert_eq!(poll_signal(all.as_mut()), vec![false]);
+        assert_eq!(poll_signal(any.as_mut()), vec![true]);
+
+        vec.lock_mut().push(5);
+        assert_eq!(poll_signal(all.as_mut()), vec![]);
+        assert_eq!(poll_signal(any.as_mut()), vec![]);
+
+        // removed items are uncounted by their position
+        vec.lock_mut().move_from_to(0, 3);
+        vec.lock_mut().remove(0);
+        vec.lock_mut().remove(2);
+        assert_eq!(vec.lock_ref().as_slice(), &[6, 5]);
+        assert_eq!(poll_signal(all.as_mut()), vec![]);
+        assert_eq!(poll_signal(any.as_mut()), vec![]);
+        vec.lock_mut().remove(1);
+        assert_eq!(poll_signal(all.as_mut()), vec![true]);
+        assert_eq!(poll_signal(any.as_mut()), vec![false]);
+        vec.lock_mut().push(3);
+        assert_eq!(poll_signal(all.as_mut()), vec![false]);
+        assert_eq!(poll_signal(any.as_mut()), vec![true]);
+
+        vec.lock_mut().clear();
+        assert_eq!(poll_signal(all.as_mut()), vec![true]);
+        assert_eq!(poll_signal(any.as_mut()), vec![false]);
+    }
+}
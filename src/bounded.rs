@@ -0,0 +1,229 @@
+use futures_signals::{
+    signal::{Mutable, Signal},
+    signal_vec::{MutableSignalVec, MutableVec, MutableVecLockMut, MutableVecLockRef},
+};
+
+/// What to do when an item is pushed into full `BoundedMutableVec`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    /// The first (oldest) item is removed to make room for the pushed one.
+    DropOldest,
+    /// The pushed item is dropped and returned back to the caller.
+    DropNewest,
+    /// The pushed item is returned back to the caller, not counted as dropped.
+    Reject,
+}
+
+/// `MutableVec` holding at most `capacity` items. Items are added only through
+/// the bounded methods, the vec itself is exposed read only.
+#[derive(Debug)]
+pub struct BoundedMutableVec<A> {
+    vec: MutableVec<A>,
+    capacity: usize,
+    overflow: Overflow,
+    dropped: Mutable<usize>,
+}
+
+impl<A> Clone for BoundedMutableVec<A> {
+    fn clone(&self) -> Self {
+        Self {
+            vec: self.vec.clone(),
+            capacity: self.capacity,
+            overflow: self.overflow,
+            dropped: self.dropped.clone(),
+        }
+    }
+}
+
+impl<A> BoundedMutableVec<A> {
+    pub fn new(capacity: usize, overflow: Overflow) -> Self {
+        Self {
+            vec: MutableVec::with_capacity(capacity),
+            capacity,
+            overflow,
+            dropped: Mutable::new(0),
+        }
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    #[inline]
+    pub fn overflow(&self) -> Overflow {
+        self.overflow
+    }
+
+    pub fn dropped_count(&self) -> usize {
+        self.dropped.get()
+    }
+
+    pub fn signal_dropped_count(&self) -> impl Signal<Item = usize> + use<A> {
+        self.dropped.signal()
+    }
+
+    pub fn lock_ref(&self) -> MutableVecLockRef<'_, A> {
+        self.vec.lock_ref()
+    }
+
+    pub fn signal_vec(&self) -> MutableSignalVec<A>
+    where
+        A: Copy,
+    {
+        self.vec.signal_vec()
+    }
+
+    pub fn signal_vec_cloned(&self) -> MutableSignalVec<A>
+    where
+        A: Clone,
+    {
+        self.vec.signal_vec_cloned()
+    }
+
+    pub fn remove(&self, index: usize) -> A {
+        self.vec.lock_mut().remove(index)
+    }
+
+    pub fn pop(&self) -> Option<A> {
+        self.vec.lock_mut().pop()
+    }
+
+    pub fn clear(&self) {
+        self.vec.lock_mut().clear()
+    }
+
+    /// Returns the value back if it was not stored, see `Overflow`.
+    pub fn push(&self, value: A) -> Result<(), A>
+    where
+        A: Copy,
+    {
+        self.store(&mut self.vec.lock_mut(), value, |lock, value| {
+            lock.push(value)
+        })
+    }
+
+    /// Returns the value back if it was not stored, see `Overflow`.
+    pub fn push_cloned(&self, value: A) -> Result<(), A>
+    where
+        A: Clone,
+    {
+        self.store(&mut self.vec.lock_mut(), value, |lock, value| {
+            lock.push_cloned(value)
+        })
+    }
+
+    /// Returns number of items stored. With `Overflow::Reject` stops at the first
+    /// rejected item.
+    pub fn extend(&self, source: impl IntoIterator<Item = A>) -> usize
+    where
+        A: Copy,
+    {
+        self.extend_with(source, |lock, value| lock.push(value))
+    }
+
+    /// Returns number of items stored. With `Overflow::Reject` stops at the first
+    /// rejected item.
+    pub fn extend_cloned(&self, source: impl IntoIterator<Item = A>) -> usize
+    where
+        A: Clone,
+    {
+        self.extend_with(source, |lock, value| lock.push_cloned(value))
+    }
+
+    fn extend_with<P>(&self, source: impl IntoIterator<Item = A>, mut push: P) -> usize
+    where
+        P: FnMut(&mut MutableVecLockMut<A>, A),
+    {
+        let mut lock = self.vec.lock_mut();
+        let mut stored = 0;
+        for item in source.into_iter() {
+            match self.store(&mut lock, item, &mut push) {
+                Ok(()) => stored += 1,
+                Err(_) if self.overflow == Overflow::Reject => break,
+                Err(_) => {}
+            }
+        }
+        stored
+    }
+
+    fn store<P>(&self, lock: &mut MutableVecLockMut<A>, value: A, mut push: P) -> Result<(), A>
+    where
+        P: FnMut(&mut MutableVecLockMut<A>, A),
+    {
+        if lock.len() < self.capacity {
+            push(lock, value);
+            return Ok(());
+        }
+
+        match self.overflow {
+            Overflow::DropOldest if self.capacity > 0 => {
+                lock.remove(0);
+                push(lock, value);
+                self.dropped.replace_with(|dropped| *dropped + 1);
+                Ok(())
+            }
+            Overflow::DropOldest | Overflow::DropNewest => {
+                self.dropped.replace_with(|dropped| *dropped + 1);
+                Err(value)
+            }
+            Overflow::Reject => Err(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::pin::pin;
+
+    use futures_signals::signal_vec::VecDiff;
+
+    use crate::{
+        BoundedMutableVec, Overflow,
+        testing::{poll_signal, poll_vec},
+    };
+
+    #[test]
+    fn drop_oldest() {
+        let vec = BoundedMutableVec::new(3, Overflow::DropOldest);
+        assert_eq!(vec.extend([1, 2, 3]), 3);
+        let mut signal = pin!(vec.signal_vec());
+        poll_vec(signal.as_mut());
+
+        assert_eq!(vec.push(4), Ok(()));
+        assert_eq!(
+            poll_vec(signal.as_mut()),
+            vec![VecDiff::RemoveAt { index: 0 }, VecDiff::Push { value: 4 }]
+        );
+        assert_eq!(vec.lock_ref().as_slice(), &[2, 3, 4]);
+        assert_eq!(vec.dropped_count(), 1);
+    }
+
+    #[test]
+    fn drop_newest_and_reject() {
+        let vec = BoundedMutableVec::new(2, Overflow::DropNewest);
+        assert_eq!(vec.extend([1, 2, 3, 4]), 2);
+        assert_eq!(vec.push(5), Err(5));
+        assert_eq!(vec.lock_ref().as_slice(), &[1, 2]);
+        assert_eq!(vec.dropped_count(), 3);
+
+        let vec = BoundedMutableVec::new(2, Overflow::Reject);
+        assert_eq!(vec.extend([1, 2, 3, 4]), 2);
+        assert_eq!(vec.push(5), Err(5));
+        assert_eq!(vec.dropped_count(), 0);
+    }
+
+    #[test]
+    fn signal_dropped_count() {
+        let vec = BoundedMutableVec::new(1, Overflow::DropOldest);
+        let mut dropped = pin!(vec.signal_dropped_count());
+        assert_eq!(poll_signal(dropped.as_mut()), vec![0]);
+
+        assert_eq!(vec.extend([1, 2, 3]), 3);
+        assert_eq!(poll_signal(dropped.as_mut()), vec![2]);
+
+        assert_eq!(vec.pop(), Some(3));
+        assert_eq!(vec.push(4), Ok(()));
+        assert_eq!(poll_signal(dropped.as_mut()), vec![]);
+    }
+}
@@ -2,6 +2,8 @@ use std::ops::{Deref, DerefMut};
 
 use futures_signals::signal_vec::{MutableVec, MutableVecLockMut};
 
+use crate::version::bump_version;
+
 pub struct Entry<'a, V> {
     key: Option<usize>,
     lock: MutableVecLockMut<'a, V>,
@@ -64,6 +66,7 @@ impl<'a, V: Copy> Entry<'a, V> {
         match self.key {
             Some(key) => {
                 self.lock.set(key, value);
+                bump_version();
                 self
             }
             None => self,
@@ -78,6 +81,7 @@ impl<'a, V: Copy> Entry<'a, V> {
     }
 
     fn set(&mut self, value: V) {
+        bump_version();
         match self.key {
             Some(index) => {
                 self.lock.set(index, value);
@@ -91,7 +95,10 @@ impl<'a, V: Copy> Entry<'a, V> {
     }
 
     pub fn remove(mut self) -> Option<V> {
-        self.key.map(|key| self.lock.remove(key))
+        let key = self.key?;
+        let value = self.lock.remove(key);
+        bump_version();
+        Some(value)
     }
 }
 
@@ -248,6 +255,7 @@ impl<'a, V: Clone> EntryCloned<'a, V> {
         match self.key {
             Some(key) => {
                 self.lock.set_cloned(key, value);
+                bump_version();
                 self
             }
             None => self,
@@ -262,6 +270,7 @@ impl<'a, V: Clone> EntryCloned<'a, V> {
     }
 
     fn set(&mut self, value: V) {
+        bump_version();
         match self.key {
             Some(index) => {
                 self.lock.set_cloned(index, value);
@@ -275,7 +284,10 @@ impl<'a, V: Clone> EntryCloned<'a, V> {
     }
 
     pub fn remove(mut self) -> Option<V> {
-        self.key.map(|key| self.lock.remove(key))
+        let key = self.key?;
+        let value = self.lock.remove(key);
+        bump_version();
+        Some(value)
     }
 }
 
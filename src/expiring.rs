@@ -0,0 +1,488 @@
+use std::{
+    future::{Future, poll_fn},
+    mem,
+    pin::pin,
+    sync::{Arc, Mutex, Weak},
+    task::{Poll, Waker},
+};
+
+use futures_signals::signal_vec::{
+    MutableSignalVec, MutableVec, MutableVecLockMut, MutableVecLockRef,
+};
+
+use crate::version::bump_version;
+
+#[derive(Debug, Default)]
+struct ExpiryState {
+    canceled: bool,
+    waker: Option<Waker>,
+}
+
+/// Pending removal of an item pushed with time-to-live.
+#[derive(Clone, Debug, Default)]
+pub struct Expiry(Arc<Mutex<ExpiryState>>);
+
+impl Expiry {
+    /// The item is kept, the timer is dropped right away.
+    pub fn cancel(&self) {
+        let mut state = self.0.lock().unwrap();
+        state.canceled = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    // true if the timer elapsed, false if canceled before
+    async fn wait(&self, timer: impl Future<Output = ()>) -> bool {
+        let mut timer = pin!(timer);
+        poll_fn(|cx| {
+            {
+                let mut state = self.0.lock().unwrap();
+                if state.canceled {
+                    return Poll::Ready(false);
+                }
+                state.waker = Some(cx.waker().clone());
+            }
+            timer.as_mut().poll(cx).map(|()| true)
+        })
+        .await
+    }
+
+    // false if canceled meanwhile, later cancel has no effect
+    fn expire(&self) -> bool {
+        !mem::replace(&mut self.0.lock().unwrap().canceled, true)
+    }
+}
+
+// Removes the first item equal to value, see `MutableVecExt::push_with_ttl`.
+pub(crate) fn expire_equal<A, F>(
+    vec: MutableVec<A>,
+    value: A,
+    timer: F,
+) -> (Expiry, impl Future<Output = ()>)
+where
+    A: PartialEq,
+    F: Future<Output = ()>,
+{
+    let expiry = Expiry::default();
+    let handle = expiry.clone();
+    let task = async move {
+        if handle.wait(timer).await && handle.expire() {
+            let mut lock = vec.lock_mut();
+            if let Some(index) = lock.iter().position(|item| *item == value) {
+                lock.remove(index);
+                bump_version();
+            }
+        }
+    };
+    (expiry, task)
+}
+
+// Item identity, the pending removal is canceled when dropped.
+#[derive(Debug)]
+struct Slot {
+    id: u64,
+    expiry: Option<Expiry>,
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        if let Some(expiry) = self.expiry.take() {
+            expiry.cancel();
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Slots {
+    // slot of the item at the same index, ids are never reused
+    slots: Vec<Slot>,
+    next: u64,
+}
+
+impl Slots {
+    fn next_slot(&mut self, expiry: Option<Expiry>) -> Slot {
+        self.next += 1;
+        Slot {
+            id: self.next,
+            expiry,
+        }
+    }
+}
+
+/// `MutableVec` with items removed after their time-to-live elapses. The `timer`
+/// factory creates a future for given time-to-live (e.g. `Duration`), the removal
+/// is spawned (locally with `_local` methods) and canceled if the item is removed
+/// or refreshed earlier, or if all clones of the vec are dropped.
+///
+/// Items have to be changed through `ExpiringMutableVec` only, so it is not
+/// dereferencing to the underlying `MutableVec`.
+#[derive(Debug)]
+pub struct ExpiringMutableVec<A, T> {
+    vec: MutableVec<A>,
+    slots: Arc<Mutex<Slots>>,
+    timer: Arc<T>,
+}
+
+impl<A, T> Clone for ExpiringMutableVec<A, T> {
+    fn clone(&self) -> Self {
+        Self {
+            vec: self.vec.clone(),
+            slots: self.slots.clone(),
+            timer: self.timer.clone(),
+        }
+    }
+}
+
+impl<A, T> ExpiringMutableVec<A, T>
+where
+    A: 'static,
+{
+    pub fn new(timer: T) -> Self {
+        Self {
+            vec: MutableVec::new(),
+            slots: Arc::new(Mutex::new(Slots::default())),
+            timer: Arc::new(timer),
+        }
+    }
+
+    #[inline]
+    pub fn lock_ref(&self) -> MutableVecLockRef<'_, A> {
+        self.vec.lock_ref()
+    }
+
+    pub fn signal_vec(&self) -> MutableSignalVec<A>
+    where
+        A: Copy,
+    {
+        self.vec.signal_vec()
+    }
+
+    pub fn signal_vec_cloned(&self) -> MutableSignalVec<A>
+    where
+        A: Clone,
+    {
+        self.vec.signal_vec_cloned()
+    }
+
+    /// Pushed item never expires.
+    pub fn push(&self, value: A)
+    where
+        A: Copy,
+    {
+        self.store(value, None, |lock, value| lock.push(value));
+    }
+
+    /// Pushed item never expires.
+    pub fn push_cloned(&self, value: A)
+    where
+        A: Clone,
+    {
+        self.store(value, None, |lock, value| lock.push_cloned(value));
+    }
+
+    #[cfg(feature = "spawn")]
+    pub fn push_with_ttl<D, F>(&self, value: A, ttl: D)
+    where
+        A: Copy + Send + Sync,
+        T: Fn(D) -> F,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        artwrap::spawn(
+            self.store_expiring(value, |lock, value| lock.push(value))
+                .after((self.timer)(ttl)),
+        );
+    }
+
+    #[cfg(feature = "spawn")]
+    pub fn push_with_ttl_cloned<D, F>(&self, value: A, ttl: D)
+    where
+        A: Clone + Send + Sync,
+        T: Fn(D) -> F,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        artwrap::spawn(
+            self.store_expiring(value, |lock, value| lock.push_cloned(value))
+                .after((self.timer)(ttl)),
+        );
+    }
+
+    /// Restarts time-to-live of the item, pending removal is canceled.
+    #[cfg(feature = "spawn")]
+    pub fn refresh<D, F>(&self, index: usize, ttl: D)
+    where
+        A: Send + Sync,
+        T: Fn(D) -> F,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        artwrap::spawn(self.refresh_expiring(index).after((self.timer)(ttl)));
+    }
+
+    #[cfg(feature = "spawn-local")]
+    pub fn push_with_ttl_local<D, F>(&self, value: A, ttl: D)
+    where
+        A: Copy,
+        T: Fn(D) -> F,
+        F: Future<Output = ()> + 'static,
+    {
+        artwrap::spawn_local(
+            self.store_expiring(value, |lock, value| lock.push(value))
+                .after((self.timer)(ttl)),
+        );
+    }
+
+    #[cfg(feature = "spawn-local")]
+    pub fn push_with_ttl_local_cloned<D, F>(&self, value: A, ttl: D)
+    where
+        A: Clone,
+        T: Fn(D) -> F,
+        F: Future<Output = ()> + 'static,
+    {
+        artwrap::spawn_local(
+            self.store_expiring(value, |lock, value| lock.push_cloned(value))
+                .after((self.timer)(ttl)),
+        );
+    }
+
+    /// Restarts time-to-live of the item, pending removal is canceled.
+    #[cfg(feature = "spawn-local")]
+    pub fn refresh_local<D, F>(&self, index: usize, ttl: D)
+    where
+        T: Fn(D) -> F,
+        F: Future<Output = ()> + 'static,
+    {
+        artwrap::spawn_local(self.refresh_expiring(index).after((self.timer)(ttl)));
+    }
+
+    /// Item will never expire, pending removal is canceled.
+    pub fn persist(&self, index: usize) {
+        let mut slots = self.slots.lock().unwrap();
+        slots.slots[index] = slots.next_slot(None);
+    }
+
+    pub fn remove(&self, index: usize) -> A {
+        let mut slots = self.slots.lock().unwrap();
+        slots.slots.remove(index);
+        let value = self.vec.lock_mut().remove(index);
+        bump_version();
+        value
+    }
+
+    pub fn find_remove<P>(&self, p: P) -> bool
+    where
+        P: FnMut(&A) -> bool,
+    {
+        let mut slots = self.slots.lock().unwrap();
+        let mut lock = self.vec.lock_mut();
+        match lock.iter().position(p) {
+            Some(index) => {
+                slots.slots.remove(index);
+                lock.remove(index);
+                bump_version();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn clear(&self) {
+        let mut slots = self.slots.lock().unwrap();
+        slots.slots.clear();
+        self.vec.lock_mut().clear();
+        bump_version();
+    }
+
+    fn store<P>(&self, value: A, expiry: Option<Expiry>, push: P) -> u64
+    where
+        P: FnOnce(&mut MutableVecLockMut<A>, A),
+    {
+        let mut slots = self.slots.lock().unwrap();
+        let slot = slots.next_slot(expiry);
+        let id = slot.id;
+        slots.slots.push(slot);
+        push(&mut self.vec.lock_mut(), value);
+        bump_version();
+        id
+    }
+
+    fn store_expiring<P>(&self, value: A, push: P) -> Removal<A>
+    where
+        P: FnOnce(&mut MutableVecLockMut<A>, A),
+    {
+        let expiry = Expiry::default();
+        let id = self.store(value, Some(expiry.clone()), push);
+        self.removal(id, expiry)
+    }
+
+    fn refresh_expiring(&self, index: usize) -> Removal<A> {
+        let expiry = Expiry::default();
+        let id = {
+            let mut slots = self.slots.lock().unwrap();
+            let slot = slots.next_slot(Some(expiry.clone()));
+            let id = slot.id;
+            slots.slots[index] = slot;
+            id
+        };
+        self.removal(id, expiry)
+    }
+
+    fn removal(&self, id: u64, expiry: Expiry) -> Removal<A> {
+        Removal {
+            vec: self.vec.clone(),
+            slots: Arc::downgrade(&self.slots),
+            id,
+            expiry,
+        }
+    }
+}
+
+// Pending removal of the item with the id, the task keeps the slots weakly.
+struct Removal<A> {
+    vec: MutableVec<A>,
+    slots: Weak<Mutex<Slots>>,
+    id: u64,
+    expiry: Expiry,
+}
+
+impl<A> Removal<A> {
+    async fn after(self, timer: impl Future<Output = ()>) {
+        if !self.expiry.wait(timer).await {
+            return;
+        }
+
+        if let Some(slots) = self.slots.upgrade() {
+            let mut slots = slots.lock().unwrap();
+            // not found if removed or refreshed meanwhile
+            if let Some(index) = slots.slots.iter().position(|slot| slot.id == self.id) {
+                slots.slots.remove(index);
+                self.vec.lock_mut().remove(index);
+                bump_version();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::future::Future;
+
+    use futures_signals::{
+        signal::{Mutable, SignalExt},
+        signal_vec::MutableVec,
+    };
+
+    use super::{ExpiringMutableVec, expire_equal};
+    use crate::testing::{run_tasks, spawn};
+
+    async fn timer(elapsed: Mutable<bool>) {
+        elapsed.signal().wait_for(true).await;
+    }
+
+    // the removal is spawned by the test instead of the runtime
+    fn push_with_ttl<T, F>(vec: &ExpiringMutableVec<i32, T>, value: i32, elapsed: &Mutable<bool>)
+    where
+        T: Fn(Mutable<bool>) -> F,
+        F: Future<Output = ()> + 'static,
+    {
+        let timer = (vec.timer)(elapsed.clone());
+        spawn(
+            vec.store_expiring(value, |lock, value| lock.push(value))
+                .after(timer),
+        );
+    }
+
+    fn refresh<T, F>(vec: &ExpiringMutableVec<i32, T>, index: usize, elapsed: &Mutable<bool>)
+    where
+        T: Fn(Mutable<bool>) -> F,
+        F: Future<Output = ()> + 'static,
+    {
+        let timer = (vec.timer)(elapsed.clone());
+        spawn(vec.refresh_expiring(index).after(timer));
+    }
+
+    #[test]
+    fn expire() {
+        let vec = ExpiringMutableVec::new(timer);
+        let (a, b) = (Mutable::new(false), Mutable::new(false));
+        push_with_ttl(&vec, 1, &a);
+        push_with_ttl(&vec, 2, &b);
+        vec.push(3);
+        assert_eq!(run_tasks(), 2);
+
+        b.set(true);
+        assert_eq!(run_tasks(), 1);
+        assert_eq!(vec.lock_ref().as_slice(), &[1, 3]);
+
+        a.set(true);
+        assert_eq!(run_tasks(), 0);
+        assert_eq!(vec.lock_ref().as_slice(), &[3]);
+    }
+
+    #[test]
+    fn refresh_ttl() {
+        let vec = ExpiringMutableVec::new(timer);
+        let (a, b) = (Mutable::new(false), Mutable::new(false));
+        push_with_ttl(&vec, 1, &a);
+        refresh(&vec, 0, &b);
+        // the replaced timer is dropped right away
+        assert_eq!(run_tasks(), 1);
+
+        a.set(true);
+        run_tasks();
+        assert_eq!(vec.lock_ref().as_slice(), &[1]);
+
+        b.set(true);
+        assert_eq!(run_tasks(), 0);
+        assert!(vec.lock_ref().is_empty());
+    }
+
+    #[test]
+    fn remove_before_ttl() {
+        let vec = ExpiringMutableVec::new(timer);
+        let (a, b) = (Mutable::new(false), Mutable::new(false));
+        push_with_ttl(&vec, 1, &a);
+        push_with_ttl(&vec, 2, &b);
+        assert_eq!(vec.remove(0), 1);
+        assert!(vec.find_remove(|item| *item == 2));
+        assert_eq!(run_tasks(), 0);
+
+        vec.push(3);
+        a.set(true);
+        b.set(true);
+        run_tasks();
+        assert_eq!(vec.lock_ref().as_slice(), &[3]);
+    }
+
+    #[test]
+    fn drop_cancels() {
+        let vec = ExpiringMutableVec::new(timer);
+        let a = Mutable::new(false);
+        push_with_ttl(&vec, 1, &a);
+        push_with_ttl(&vec, 2, &a);
+        let clone = vec.clone();
+        drop(vec);
+        assert_eq!(run_tasks(), 2);
+
+        drop(clone);
+        assert_eq!(run_tasks(), 0);
+    }
+
+    #[test]
+    fn expire_equal_item() {
+        let vec = MutableVec::new_with_values(vec![1, 2]);
+        let (a, b) = (Mutable::new(false), Mutable::new(false));
+        let (_, task) = expire_equal(vec.clone(), 1, timer(a.clone()));
+        spawn(task);
+        let (expiry, task) = expire_equal(vec.clone(), 2, timer(b.clone()));
+        spawn(task);
+        assert_eq!(run_tasks(), 2);
+
+        expiry.cancel();
+        assert_eq!(run_tasks(), 1);
+
+        a.set(true);
+        b.set(true);
+        assert_eq!(run_tasks(), 0);
+        assert_eq!(vec.lock_ref().as_slice(), &[2]);
+    }
+}
@@ -1,5 +1,5 @@
 use futures_signals::{
-    signal::{Mutable, Signal, SignalExt},
+    signal::{Always, Mutable, MutableSignal, Signal, SignalExt, always},
     signal_vec::{
         Filter, FilterMap, FilterSignalCloned, MutableSignalVec, MutableVec, MutableVecLockMut,
         SignalVec, SignalVecExt,
@@ -7,24 +7,40 @@ use futures_signals::{
 };
 use pin_project_lite::pin_project;
 use std::{
+    cmp::Ordering,
     collections::VecDeque,
+    future::Future,
     hash::Hash,
     marker::PhantomData,
     mem,
+    ops::{Add, Sub},
     pin::Pin,
     task::{Context, Poll},
 };
 
-use crate::{Flatten, MutableVecEntry, SignalVecSpawn};
+use crate::{
+    Aggregate, Aggregator, All, Any, AverageBy, Chunks, Concat, CountWhere, DistinctByKey,
+    DuplicateKeys, FilterBySignal, FindMap, Flatten, FlattenCoalesced, FlattenIndexed, FlattenWith,
+    FoldGroup, Frames, GroupBy, IndexedOuter, JoinByKey, JoinKind, MergeSorted, MinMaxByKey,
+    MutableVecEntry, Nth, NthSignal, Partition, Position, Reverse, SetByKey, SignalMapByKey,
+    SignalVecSpawn, SortBySignal, SumBy, Transaction, Window, WithNeighbors,
+    group::Locate,
+    partition::partition,
+    set::SetOperation,
+    version::{self, bump_version},
+};
+
+#[cfg(any(feature = "spawn", feature = "spawn-local"))]
+use crate::{Expiry, expiring::expire_equal};
 
 #[cfg(feature = "ahash")]
-type Hasher = ahash::RandomState;
+pub(crate) type Hasher = ahash::RandomState;
 #[cfg(not(feature = "ahash"))]
-type Hasher = std::hash::RandomState;
+pub(crate) type Hasher = std::hash::RandomState;
 
-type HashMap<K, V> = std::collections::HashMap<K, V, Hasher>;
+pub(crate) type HashMap<K, V> = std::collections::HashMap<K, V, Hasher>;
 
-fn collect_hash_map<K, V, I>(iter: I) -> HashMap<K, V>
+pub(crate) fn collect_hash_map<K, V, I>(iter: I) -> HashMap<K, V>
 where
     K: Eq + Hash,
     I: Iterator<Item = (K, V)>,
@@ -60,6 +76,15 @@ pub trait MutableExt<A> {
     {
         self.map_mut(mem::take)
     }
+
+    /// See `MutableVecExt::version`, changes made by `inspect_mut` and `map_mut` are counted.
+    fn version(&self) -> u64 {
+        version::version()
+    }
+
+    fn signal_version(&self) -> MutableSignal<u64> {
+        version::signal_version()
+    }
 }
 
 impl<A> MutableExt<A> for Mutable<A> {
@@ -68,7 +93,8 @@ impl<A> MutableExt<A> for Mutable<A> {
     }
 
     fn inspect_mut(&self, mut f: impl FnMut(&mut A)) {
-        f(&mut self.lock_mut())
+        f(&mut self.lock_mut());
+        bump_version();
     }
 
     fn map<B>(&self, f: impl FnOnce(&A) -> B) -> B {
@@ -76,7 +102,9 @@ impl<A> MutableExt<A> for Mutable<A> {
     }
 
     fn map_mut<B>(&self, f: impl FnOnce(&mut A) -> B) -> B {
-        f(&mut self.lock_mut())
+        let result = f(&mut self.lock_mut());
+        bump_version();
+        result
     }
 }
 
@@ -242,6 +270,29 @@ pub trait MutableVecExt<A> {
 
     fn take(&self) -> Vec<A>;
 
+    fn transaction<F, U>(&self, f: F) -> U
+    where
+        A: Copy,
+        F: FnOnce(&mut Transaction<A>) -> U;
+
+    /// Changes done in F are coalesced and applied when F returns, see `Transaction`.
+    fn transaction_cloned<F, U>(&self, f: F) -> U
+    where
+        A: Clone,
+        F: FnOnce(&mut Transaction<A>) -> U;
+
+    /// Version bumped on every change made through this crate (methods of this trait,
+    /// entries, transactions, feeding, expiries). Changes made directly through
+    /// `lock_mut` are not counted. The version is shared by all vecs and mutables,
+    /// so the same version means no change, a different one a possible change.
+    fn version(&self) -> u64 {
+        version::version()
+    }
+
+    fn signal_version(&self) -> MutableSignal<u64> {
+        version::signal_version()
+    }
+
     #[cfg(feature = "spawn")]
     fn feed(&self, source: impl SignalVec<Item = A> + Send + 'static)
     where
@@ -262,6 +313,32 @@ pub trait MutableVecExt<A> {
     where
         A: Clone + 'static;
 
+    /// The first item equal to the value is removed when `timer` completes, unless
+    /// the returned `Expiry` is canceled. See `ExpiringMutableVec` for refreshing.
+    #[cfg(feature = "spawn")]
+    fn push_with_ttl<F>(&self, value: A, timer: F) -> Expiry
+    where
+        A: Copy + PartialEq + Send + Sync + 'static,
+        F: Future<Output = ()> + Send + 'static;
+
+    #[cfg(feature = "spawn")]
+    fn push_with_ttl_cloned<F>(&self, value: A, timer: F) -> Expiry
+    where
+        A: Clone + PartialEq + Send + Sync + 'static,
+        F: Future<Output = ()> + Send + 'static;
+
+    #[cfg(feature = "spawn-local")]
+    fn push_with_ttl_local<F>(&self, value: A, timer: F) -> Expiry
+    where
+        A: Copy + PartialEq + 'static,
+        F: Future<Output = ()> + 'static;
+
+    #[cfg(feature = "spawn-local")]
+    fn push_with_ttl_local_cloned<F>(&self, value: A, timer: F) -> Expiry
+    where
+        A: Clone + PartialEq + 'static,
+        F: Future<Output = ()> + 'static;
+
     fn signal_vec_filter<P>(&self, p: P) -> Filter<MutableSignalVec<A>, P>
     where
         A: Copy,
@@ -287,6 +364,26 @@ pub trait MutableVecExt<A> {
         P: FnMut(&A) -> S,
         S: Signal<Item = bool>;
 
+    fn signal_vec_filter_by_signal<C, P>(
+        &self,
+        criteria: C,
+        p: P,
+    ) -> FilterBySignal<MutableSignalVec<A>, C, P>
+    where
+        A: Copy,
+        C: Signal,
+        P: FnMut(&C::Item, &A) -> bool;
+
+    fn signal_vec_filter_by_signal_cloned<C, P>(
+        &self,
+        criteria: C,
+        p: P,
+    ) -> FilterBySignal<MutableSignalVec<A>, C, P>
+    where
+        A: Clone,
+        C: Signal,
+        P: FnMut(&C::Item, &A) -> bool;
+
     fn signal_vec_filter_map<P, U>(&self, p: P) -> FilterMap<MutableSignalVec<A>, P>
     where
         A: Copy,
@@ -306,7 +403,8 @@ impl<A> MutableVecExt<A> for MutableVec<A> {
 
     #[inline]
     fn inspect_vec_mut(&self, mut f: impl FnMut(&mut MutableVecLockMut<A>)) {
-        f(&mut self.lock_mut())
+        f(&mut self.lock_mut());
+        bump_version();
     }
 
     fn map_vec<F, U>(&self, f: F) -> U
@@ -320,7 +418,9 @@ impl<A> MutableVecExt<A> for MutableVec<A> {
     where
         F: FnOnce(&mut MutableVecLockMut<A>) -> U,
     {
-        f(&mut self.lock_mut())
+        let result = f(&mut self.lock_mut());
+        bump_version();
+        result
     }
 
     /// Return parameter of F (changed) drives if the value should be written back,
@@ -552,9 +652,13 @@ impl<A> MutableVecExt<A> for MutableVec<A> {
         A: Copy,
     {
         let mut lock = self.lock_mut();
+        let len = lock.len();
         for item in source.into_iter() {
             lock.push(item);
         }
+        if lock.len() != len {
+            bump_version();
+        }
     }
 
     fn extend_cloned(&self, source: impl IntoIterator<Item = A>)
@@ -562,9 +666,13 @@ impl<A> MutableVecExt<A> for MutableVec<A> {
         A: Clone,
     {
         let mut lock = self.lock_mut();
+        let len = lock.len();
         for item in source.into_iter() {
             lock.push_cloned(item);
         }
+        if lock.len() != len {
+            bump_version();
+        }
     }
 
     fn replace<P>(&self, mut what: P, with: impl IntoIterator<Item = A>)
@@ -573,9 +681,15 @@ impl<A> MutableVecExt<A> for MutableVec<A> {
         P: FnMut(&A) -> bool,
     {
         let mut lock = self.lock_mut();
+        let len = lock.len();
         lock.retain(|item| !what(item));
+        let mut changed = lock.len() != len;
         for item in with.into_iter() {
             lock.push(item);
+            changed = true;
+        }
+        if changed {
+            bump_version();
         }
     }
 
@@ -585,9 +699,15 @@ impl<A> MutableVecExt<A> for MutableVec<A> {
         P: FnMut(&A) -> bool,
     {
         let mut lock = self.lock_mut();
+        let len = lock.len();
         lock.retain(|item| !what(item));
+        let mut changed = lock.len() != len;
         for item in with.into_iter() {
             lock.push_cloned(item);
+            changed = true;
+        }
+        if changed {
+            bump_version();
         }
     }
 
@@ -607,6 +727,7 @@ impl<A> MutableVecExt<A> for MutableVec<A> {
             .enumerate()
             .filter_map(|(index, item)| source.remove(&key(item)).map(|item| (index, item)))
             .collect::<Vec<_>>();
+        let replaced = !to_replace.is_empty();
         for (index, item) in to_replace {
             lock.set(index, item)
         }
@@ -616,6 +737,9 @@ impl<A> MutableVecExt<A> for MutableVec<A> {
             lock.push(item);
         }
 
+        if replaced || extended {
+            bump_version();
+        }
         extended
     }
 
@@ -635,6 +759,7 @@ impl<A> MutableVecExt<A> for MutableVec<A> {
             .enumerate()
             .filter_map(|(index, item)| source.remove(&key(item)).map(|item| (index, item)))
             .collect::<Vec<_>>();
+        let replaced = !to_replace.is_empty();
         for (index, item) in to_replace {
             lock.set_cloned(index, item)
         }
@@ -644,6 +769,9 @@ impl<A> MutableVecExt<A> for MutableVec<A> {
             lock.push_cloned(item);
         }
 
+        if replaced || extended {
+            bump_version();
+        }
         extended
     }
 
@@ -667,6 +795,7 @@ impl<A> MutableVecExt<A> for MutableVec<A> {
                 None => Some(index),
             })
             .collect();
+        let removed = !to_remove.is_empty();
         // indexes go down, no need to calculate them anyhow
         for index in to_remove.into_iter() {
             lock.remove(index);
@@ -677,6 +806,9 @@ impl<A> MutableVecExt<A> for MutableVec<A> {
             lock.push(item);
         }
 
+        if removed || extended {
+            bump_version();
+        }
         extended
     }
 
@@ -700,6 +832,7 @@ impl<A> MutableVecExt<A> for MutableVec<A> {
                 None => Some(index),
             })
             .collect::<Vec<_>>();
+        let removed = !to_remove.is_empty();
         // indexes go down, no need to calculate them anyhow
         for index in to_remove.into_iter() {
             lock.remove(index);
@@ -710,11 +843,38 @@ impl<A> MutableVecExt<A> for MutableVec<A> {
             lock.push_cloned(item);
         }
 
+        if removed || extended {
+            bump_version();
+        }
         extended
     }
 
     fn take(&self) -> Vec<A> {
-        self.lock_mut().drain(..).collect()
+        let values = self.lock_mut().drain(..).collect::<Vec<_>>();
+        if !values.is_empty() {
+            bump_version();
+        }
+        values
+    }
+
+    fn transaction<F, U>(&self, f: F) -> U
+    where
+        A: Copy,
+        F: FnOnce(&mut Transaction<A>) -> U,
+    {
+        self.transaction_cloned(f)
+    }
+
+    fn transaction_cloned<F, U>(&self, f: F) -> U
+    where
+        A: Clone,
+        F: FnOnce(&mut Transaction<A>) -> U,
+    {
+        let mut lock = self.lock_mut();
+        let mut transaction = Transaction::new(lock.to_vec());
+        let result = f(&mut transaction);
+        transaction.commit(&mut lock, None);
+        result
     }
 
     #[cfg(feature = "spawn")]
@@ -749,6 +909,58 @@ impl<A> MutableVecExt<A> for MutableVec<A> {
         source.feed_local_cloned(self.clone());
     }
 
+    #[cfg(feature = "spawn")]
+    fn push_with_ttl<F>(&self, value: A, timer: F) -> Expiry
+    where
+        A: Copy + PartialEq + Send + Sync + 'static,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.lock_mut().push(value);
+        bump_version();
+        let (expiry, task) = expire_equal(self.clone(), value, timer);
+        artwrap::spawn(task);
+        expiry
+    }
+
+    #[cfg(feature = "spawn")]
+    fn push_with_ttl_cloned<F>(&self, value: A, timer: F) -> Expiry
+    where
+        A: Clone + PartialEq + Send + Sync + 'static,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.lock_mut().push_cloned(value.clone());
+        bump_version();
+        let (expiry, task) = expire_equal(self.clone(), value, timer);
+        artwrap::spawn(task);
+        expiry
+    }
+
+    #[cfg(feature = "spawn-local")]
+    fn push_with_ttl_local<F>(&self, value: A, timer: F) -> Expiry
+    where
+        A: Copy + PartialEq + 'static,
+        F: Future<Output = ()> + 'static,
+    {
+        self.lock_mut().push(value);
+        bump_version();
+        let (expiry, task) = expire_equal(self.clone(), value, timer);
+        artwrap::spawn_local(task);
+        expiry
+    }
+
+    #[cfg(feature = "spawn-local")]
+    fn push_with_ttl_local_cloned<F>(&self, value: A, timer: F) -> Expiry
+    where
+        A: Clone + PartialEq + 'static,
+        F: Future<Output = ()> + 'static,
+    {
+        self.lock_mut().push_cloned(value.clone());
+        bump_version();
+        let (expiry, task) = expire_equal(self.clone(), value, timer);
+        artwrap::spawn_local(task);
+        expiry
+    }
+
     #[inline]
     fn signal_vec_filter<P>(&self, p: P) -> Filter<MutableSignalVec<A>, P>
     where
@@ -790,6 +1002,34 @@ impl<A> MutableVecExt<A> for MutableVec<A> {
         self.signal_vec_cloned().filter_signal_cloned(p)
     }
 
+    #[inline]
+    fn signal_vec_filter_by_signal<C, P>(
+        &self,
+        criteria: C,
+        p: P,
+    ) -> FilterBySignal<MutableSignalVec<A>, C, P>
+    where
+        A: Copy,
+        C: Signal,
+        P: FnMut(&C::Item, &A) -> bool,
+    {
+        self.signal_vec().filter_by_signal(criteria, p)
+    }
+
+    #[inline]
+    fn signal_vec_filter_by_signal_cloned<C, P>(
+        &self,
+        criteria: C,
+        p: P,
+    ) -> FilterBySignal<MutableSignalVec<A>, C, P>
+    where
+        A: Clone,
+        C: Signal,
+        P: FnMut(&C::Item, &A) -> bool,
+    {
+        self.signal_vec_cloned().filter_by_signal(criteria, p)
+    }
+
     #[inline]
     fn signal_vec_filter_map<P, U>(&self, p: P) -> FilterMap<MutableSignalVec<A>, P>
     where
@@ -854,24 +1094,151 @@ pub trait SignalVecFinalizerExt: SignalVec + Sized {
         self.to_signal_map(move |items| items.last().map(&mut f))
     }
 
-    fn all<F>(self, mut f: F) -> impl Signal<Item = bool>
+    fn all<F>(self, f: F) -> All<Self, F>
     where
         F: FnMut(&Self::Item) -> bool,
     {
-        self.to_signal_map(move |items| items.iter().all(&mut f))
+        All::new(self, f)
     }
 
-    fn any<F>(self, mut f: F) -> impl Signal<Item = bool>
+    fn any<F>(self, f: F) -> Any<Self, F>
     where
         F: FnMut(&Self::Item) -> bool,
     {
-        self.to_signal_map(move |items| items.iter().any(&mut f))
+        Any::new(self, f)
+    }
+
+    #[inline]
+    fn nth(self, index: usize) -> Aggregate<Self, Nth<Self::Item>>
+    where
+        Self::Item: Copy + PartialEq,
+    {
+        self.nth_cloned(index)
+    }
+
+    fn nth_cloned(self, index: usize) -> Aggregate<Self, Nth<Self::Item>>
+    where
+        Self::Item: Clone + PartialEq,
+    {
+        self.aggregate(Nth::new(Some(index)))
+    }
+
+    fn nth_signal<I>(self, index: I) -> NthSignal<Self, I>
+    where
+        Self::Item: Clone + PartialEq,
+        I: Signal<Item = usize>,
+    {
+        NthSignal::new(self, index)
+    }
+
+    fn position<P>(self, p: P) -> Aggregate<Self, Position<P>>
+    where
+        P: FnMut(&Self::Item) -> bool,
+    {
+        self.aggregate(Position::new(p))
+    }
+
+    #[inline]
+    fn find<P>(self, mut p: P) -> impl Signal<Item = Option<Self::Item>>
+    where
+        Self::Item: Copy + PartialEq,
+        P: FnMut(&Self::Item) -> bool,
+    {
+        self.find_map(move |item| p(item).then_some(*item))
+    }
+
+    fn find_cloned<P>(self, mut p: P) -> impl Signal<Item = Option<Self::Item>>
+    where
+        Self::Item: Clone + PartialEq,
+        P: FnMut(&Self::Item) -> bool,
+    {
+        self.find_map(move |item| p(item).then(|| item.clone()))
+    }
+
+    fn find_map<F, U>(self, f: F) -> Aggregate<Self, FindMap<F, U>>
+    where
+        F: FnMut(&Self::Item) -> Option<U>,
+        U: PartialEq + Clone,
+    {
+        self.aggregate(FindMap::new(f))
+    }
+
+    fn contains(self, value: Self::Item) -> impl Signal<Item = bool>
+    where
+        Self::Item: PartialEq,
+    {
+        self.any(move |item| *item == value)
     }
 
     #[inline]
     fn any_item(self) -> impl Signal<Item = bool> {
         self.len().neq(0)
     }
+
+    /// Aggregated value is maintained per diff, without iterating over all items.
+    fn aggregate<G>(self, aggregator: G) -> Aggregate<Self, G>
+    where
+        G: Aggregator<Self::Item>,
+    {
+        Aggregate::new(self, aggregator)
+    }
+
+    fn sum_by<F, T>(self, f: F) -> Aggregate<Self, SumBy<F, T>>
+    where
+        F: FnMut(&Self::Item) -> T,
+        T: Copy + Default + PartialEq + Add<Output = T> + Sub<Output = T>,
+    {
+        self.aggregate(SumBy::new(f))
+    }
+
+    fn count_where<F>(self, f: F) -> Aggregate<Self, CountWhere<F>>
+    where
+        F: FnMut(&Self::Item) -> bool,
+    {
+        self.aggregate(CountWhere::new(f))
+    }
+
+    /// Of items with equal keys, the earliest inserted one is returned.
+    fn min_by_key<F, K>(self, f: F) -> Aggregate<Self, MinMaxByKey<Self::Item, F, K>>
+    where
+        Self::Item: Clone,
+        F: FnMut(&Self::Item) -> K,
+        K: Ord + Clone,
+    {
+        self.aggregate(MinMaxByKey::new(f, false))
+    }
+
+    /// Of items with equal keys, the latest inserted one is returned.
+    fn max_by_key<F, K>(self, f: F) -> Aggregate<Self, MinMaxByKey<Self::Item, F, K>>
+    where
+        Self::Item: Clone,
+        F: FnMut(&Self::Item) -> K,
+        K: Ord + Clone,
+    {
+        self.aggregate(MinMaxByKey::new(f, true))
+    }
+
+    /// `remove` is the inverse of `add`, applied to the old item on update or removal.
+    fn fold_group<T, F, R>(
+        self,
+        init: T,
+        add: F,
+        remove: R,
+    ) -> Aggregate<Self, FoldGroup<Self::Item, T, F, R>>
+    where
+        T: Clone + PartialEq,
+        F: FnMut(&mut T, &Self::Item),
+        R: FnMut(&mut T, &Self::Item),
+    {
+        self.aggregate(FoldGroup::new(init, add, remove))
+    }
+
+    fn average_by<F>(self, f: F) -> Aggregate<Self, AverageBy<F>>
+    where
+        F: FnMut(&Self::Item) -> f64,
+    {
+        self.aggregate(AverageBy::new(f))
+    }
 }
 
 impl<S: SignalVec + Sized> SignalVecFinalizerExt for S {}
@@ -883,14 +1250,268 @@ pub trait SignalVecFlattenExt: SignalVec + Sized {
     {
         Flatten {
             signal: Some(self),
-            inner: vec![],
+            inner: Default::default(),
+            coalesce: false,
             pending: VecDeque::new(),
         }
     }
+
+    /// Groups of `(group, signal vec)` flattened, each between its header and
+    /// footer.
+    fn flatten_with<G, S, FH, FF, H, F>(
+        self,
+        header: FH,
+        footer: FF,
+        hide_empty: bool,
+    ) -> FlattenWith<Self, FH, FF>
+    where
+        Self: SignalVec<Item = (G, S)>,
+        S: SignalVec,
+        FH: FnMut(&G) -> H,
+        FF: FnMut(&G) -> F,
+        H: Clone,
+        F: Clone,
+    {
+        Frames::new(self, header, footer, hide_empty).flatten_ext()
+    }
+
+    /// Items with their reactive `(outer, inner)` index path.
+    fn flatten_indexed(self) -> FlattenIndexed<Self>
+    where
+        Self::Item: SignalVec,
+    {
+        IndexedOuter::new(self).flatten_ext()
+    }
+
+    /// Emits a single `Replace` instead of more than `threshold` diffs.
+    fn flatten_coalesced(self, threshold: usize) -> FlattenCoalesced<Self>
+    where
+        Self::Item: SignalVec,
+        <Self::Item as SignalVec>::Item: Clone,
+    {
+        FlattenCoalesced::new(self, threshold)
+    }
+
+    fn concat<S>(self, other: S) -> Concat<Self, S>
+    where
+        S: SignalVec<Item = Self::Item>,
+    {
+        Concat::new(self, other)
+    }
+
+    /// Merges two sorted signal vecs, equal items are ordered left first.
+    fn merge_sorted_by<S, F>(self, other: S, cmp: F) -> MergeSorted<Self, S, F>
+    where
+        Self::Item: Clone,
+        S: SignalVec<Item = Self::Item>,
+        F: FnMut(&Self::Item, &Self::Item) -> Ordering,
+    {
+        MergeSorted::new(self, other, cmp)
+    }
 }
 
 impl<S: SignalVec + Sized> SignalVecFlattenExt for S {}
 
+pub trait SignalVecFilterExt: SignalVec + Sized {
+    /// All items are re-evaluated only when the criteria change.
+    fn filter_by_signal<C, P>(self, criteria: C, p: P) -> FilterBySignal<Self, C, P>
+    where
+        Self::Item: Clone,
+        C: Signal,
+        P: FnMut(&C::Item, &Self::Item) -> bool,
+    {
+        FilterBySignal::new(self, criteria, p)
+    }
+
+    /// Items matching the predicate and the rest, sharing the source.
+    fn partition<P>(self, p: P) -> (Partition<Self, P>, Partition<Self, P>)
+    where
+        Self::Item: Clone,
+        P: FnMut(&Self::Item) -> bool,
+    {
+        partition(self, p)
+    }
+}
+
+impl<S: SignalVec + Sized> SignalVecFilterExt for S {}
+
+pub trait SignalVecGroupExt: SignalVec + Sized {
+    /// Groups are ordered by the first appearance of their key in the source,
+    /// a group moves when its first item is removed or moved.
+    fn group_by<F, K>(self, key: F) -> GroupBy<Self, F, K>
+    where
+        Self::Item: Clone,
+        F: FnMut(&Self::Item) -> K,
+        K: Eq + Hash + Clone,
+    {
+        GroupBy::new(self, key, Locate::by_appearance())
+    }
+
+    /// Groups are ordered by key.
+    fn group_by_ord<F, K>(self, key: F) -> GroupBy<Self, F, K>
+    where
+        Self::Item: Clone,
+        F: FnMut(&Self::Item) -> K,
+        K: Ord + Clone,
+    {
+        GroupBy::new(self, key, Locate::by_key())
+    }
+}
+
+impl<S: SignalVec + Sized> SignalVecGroupExt for S {}
+
+pub trait SignalVecKeyedExt: SignalVec + Sized {
+    fn to_signal_map_by_key<F, K>(
+        self,
+        key: F,
+        duplicates: DuplicateKeys,
+    ) -> SignalMapByKey<Self, F, K>
+    where
+        Self::Item: Clone,
+        F: FnMut(&Self::Item) -> K,
+        K: Eq + Hash + Clone,
+    {
+        SignalMapByKey::new(self, key, duplicates)
+    }
+
+    fn join_by_key<R, FL, FR, K>(
+        self,
+        right: R,
+        left_key: FL,
+        right_key: FR,
+        kind: JoinKind,
+    ) -> JoinByKey<Self, R, FL, FR, K>
+    where
+        Self::Item: Clone,
+        R: SignalVec,
+        R::Item: Clone,
+        FL: FnMut(&Self::Item) -> K,
+        FR: FnMut(&R::Item) -> K,
+        K: Eq + Hash + Clone,
+    {
+        JoinByKey::new(self, right, left_key, right_key, kind)
+    }
+
+    fn distinct_by_key<F, K>(self, key: F) -> DistinctByKey<Self, F, K>
+    where
+        Self::Item: Clone,
+        F: FnMut(&Self::Item) -> K,
+        K: Eq + Hash + Clone,
+    {
+        DistinctByKey::new(self, key)
+    }
+
+    /// Items without a right item with equal key, in left order.
+    fn difference_by_key<R, F, K>(self, right: R, key: F) -> SetByKey<Self, R, F, K>
+    where
+        Self::Item: Clone,
+        R: SignalVec<Item = Self::Item>,
+        F: FnMut(&Self::Item) -> K,
+        K: Eq + Hash + Clone,
+    {
+        SetByKey::new(self, right, key, SetOperation::Difference)
+    }
+
+    /// Items with a right item with equal key, in left order.
+    fn intersection_by_key<R, F, K>(self, right: R, key: F) -> SetByKey<Self, R, F, K>
+    where
+        Self::Item: Clone,
+        R: SignalVec<Item = Self::Item>,
+        F: FnMut(&Self::Item) -> K,
+        K: Eq + Hash + Clone,
+    {
+        SetByKey::new(self, right, key, SetOperation::Intersection)
+    }
+
+    /// Left items followed by right items without a left item with equal key.
+    fn union_by_key<R, F, K>(self, right: R, key: F) -> SetByKey<Self, R, F, K>
+    where
+        Self::Item: Clone,
+        R: SignalVec<Item = Self::Item>,
+        F: FnMut(&Self::Item) -> K,
+        K: Eq + Hash + Clone,
+    {
+        SetByKey::new(self, right, key, SetOperation::Union)
+    }
+}
+
+impl<S: SignalVec + Sized> SignalVecKeyedExt for S {}
+
+pub trait SignalVecSortExt: SignalVec + Sized {
+    /// On comparator change emits `Move` diffs, or `Replace` if most items move.
+    fn sort_by_signal<C, F>(self, cmp: C) -> SortBySignal<Self, C>
+    where
+        Self::Item: Clone,
+        C: Signal<Item = F>,
+        F: FnMut(&Self::Item, &Self::Item) -> Ordering,
+    {
+        SortBySignal::new(self, cmp)
+    }
+}
+
+impl<S: SignalVec + Sized> SignalVecSortExt for S {}
+
+pub trait SignalVecReshapeExt: SignalVec + Sized {
+    fn reverse(self) -> Reverse<Self> {
+        Reverse::new(self)
+    }
+
+    /// Panics if `size` is 0.
+    fn chunks(self, size: usize) -> Chunks<Self>
+    where
+        Self::Item: Clone,
+    {
+        Chunks::new(self, size)
+    }
+
+    fn with_neighbors(self) -> WithNeighbors<Self>
+    where
+        Self::Item: Clone,
+    {
+        WithNeighbors::new(self)
+    }
+}
+
+impl<S: SignalVec + Sized> SignalVecReshapeExt for S {}
+
+pub trait SignalVecWindowExt: SignalVec + Sized {
+    fn window<O, L>(self, offset: O, len: L) -> Window<Self, O, L>
+    where
+        Self::Item: Clone,
+        O: Signal<Item = usize>,
+        L: Signal<Item = usize>,
+    {
+        Window::new(self, offset, len)
+    }
+
+    #[inline]
+    fn take(self, n: usize) -> Window<Self, Always<usize>, Always<usize>>
+    where
+        Self::Item: Clone,
+    {
+        self.window(always(0), always(n))
+    }
+
+    #[inline]
+    fn take_signal<N>(self, n: N) -> Window<Self, Always<usize>, N>
+    where
+        Self::Item: Clone,
+        N: Signal<Item = usize>,
+    {
+        self.window(always(0), n)
+    }
+
+    #[inline]
+    fn skip(self, n: usize) -> Window<Self, Always<usize>, Always<usize>>
+    where
+        Self::Item: Clone,
+    {
+        self.window(always(n), always(usize::MAX))
+    }
+}
+
+impl<S: SignalVec + Sized> SignalVecWindowExt for S {}
+
 pub trait SignalTimeExt: Signal + Sized {
     #[inline]
     fn debounce<W, F>(
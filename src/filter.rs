@@ -0,0 +1,362 @@
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_signals::{
+    signal::Signal,
+    signal_vec::{SignalVec, VecDiff},
+};
+use pin_project_lite::pin_project;
+
+// Visibility of source items, translating source diffs to diffs of the visible ones.
+#[derive(Debug, Default)]
+pub(crate) struct Visibility {
+    visible: Vec<bool>,
+}
+
+impl Visibility {
+    pub(crate) fn len(&self) -> usize {
+        self.visible.len()
+    }
+
+    pub(crate) fn visible_len(&self) -> usize {
+        self.output_index(self.visible.len())
+    }
+
+    // the visible ones of the source items
+    pub(crate) fn visible<'a, T>(&'a self, items: &'a [T]) -> impl Iterator<Item = &'a T> {
+        items
+            .iter()
+            .zip(&self.visible)
+            .filter_map(|(item, visible)| visible.then_some(item))
+    }
+
+    fn output_index(&self, index: usize) -> usize {
+        self.visible[..index]
+            .iter()
+            .filter(|visible| **visible)
+            .count()
+    }
+
+    pub(crate) fn insert<T>(
+        &mut self,
+        index: usize,
+        visible: bool,
+        value: T,
+        pending: &mut VecDeque<VecDiff<T>>,
+    ) {
+        self.visible.insert(index, visible);
+        if visible {
+            let index = self.output_index(index);
+            pending.push_back(VecDiff::InsertAt { index, value });
+        }
+    }
+
+    pub(crate) fn update<T>(
+        &mut self,
+        index: usize,
+        visible: bool,
+        value: T,
+        pending: &mut VecDeque<VecDiff<T>>,
+    ) {
+        let was_visible = self.visible[index];
+        self.visible[index] = visible;
+        let index = self.output_index(index);
+        match (was_visible, visible) {
+            (true, true) => pending.push_back(VecDiff::UpdateAt { index, value }),
+            (true, false) => pending.push_back(VecDiff::RemoveAt { index }),
+            (false, true) => pending.push_back(VecDiff::InsertAt { index, value }),
+            (false, false) => {}
+        }
+    }
+
+    pub(crate) fn remove<T>(&mut self, index: usize, pending: &mut VecDeque<VecDiff<T>>) {
+        if self.visible.remove(index) {
+            let index = self.output_index(index);
+            pending.push_back(VecDiff::RemoveAt { index });
+        }
+    }
+
+    pub(crate) fn move_item<T>(
+        &mut self,
+        old_index: usize,
+        new_index: usize,
+        pending: &mut VecDeque<VecDiff<T>>,
+    ) {
+        let old_output = self.output_index(old_index);
+        let visible = self.visible.remove(old_index);
+        self.visible.insert(new_index, visible);
+        let new_output = self.output_index(new_index);
+        if visible && old_output != new_output {
+            pending.push_back(VecDiff::Move {
+                old_index: old_output,
+                new_index: new_output,
+            });
+        }
+    }
+
+    pub(crate) fn replace<T>(
+        &mut self,
+        values: impl IntoIterator<Item = (bool, T)>,
+        pending: &mut VecDeque<VecDiff<T>>,
+    ) {
+        self.visible.clear();
+        let mut visible_values = vec![];
+        for (visible, value) in values {
+            self.visible.push(visible);
+            if visible {
+                visible_values.push(value);
+            }
+        }
+        pending.push_back(VecDiff::Replace {
+            values: visible_values,
+        });
+    }
+
+    // like `replace`, with only the visible values given
+    pub(crate) fn replace_visible<T>(
+        &mut self,
+        visible: Vec<bool>,
+        values: Vec<T>,
+        pending: &mut VecDeque<VecDiff<T>>,
+    ) {
+        self.visible = visible;
+        pending.push_back(VecDiff::Replace { values });
+    }
+
+    pub(crate) fn clear<T>(&mut self, pending: &mut VecDeque<VecDiff<T>>) {
+        self.visible.clear();
+        pending.push_back(VecDiff::Clear {});
+    }
+
+    /// Changes visibility of all items, with minimal diffs.
+    pub(crate) fn reset<T>(
+        &mut self,
+        mut visible: impl FnMut(usize) -> bool,
+        mut value: impl FnMut(usize) -> T,
+        pending: &mut VecDeque<VecDiff<T>>,
+    ) {
+        let mut output = 0;
+        for index in 0..self.visible.len() {
+            let now_visible = visible(index);
+            match (self.visible[index], now_visible) {
+                (true, false) => pending.push_back(VecDiff::RemoveAt { index: output }),
+                (false, true) => {
+                    pending.push_back(VecDiff::InsertAt {
+                        index: output,
+                        value: value(index),
+                    });
+                    output += 1;
+                }
+                (true, true) => output += 1,
+                (false, false) => {}
+            }
+            self.visible[index] = now_visible;
+        }
+    }
+}
+
+pin_project! {
+    /// Items are evaluated against the latest criteria, all of them only when the
+    /// criteria change. Nothing is emitted before the first criteria.
+    #[derive(Debug)]
+    #[must_use = "SignalVecs do nothing unless polled"]
+    pub struct FilterBySignal<S, C, P>
+    where
+        S: SignalVec,
+        C: Signal,
+    {
+        #[pin]
+        signal: Option<S>,
+        #[pin]
+        criteria_signal: Option<C>,
+        criteria: Option<C::Item>,
+        predicate: P,
+        items: Vec<S::Item>,
+        visibility: Visibility,
+        pending: VecDeque<VecDiff<S::Item>>,
+    }
+}
+
+impl<S: SignalVec, C: Signal, P> FilterBySignal<S, C, P> {
+    pub(crate) fn new(signal: S, criteria: C, predicate: P) -> Self {
+        Self {
+            signal: Some(signal),
+            criteria_signal: Some(criteria),
+            criteria: None,
+            predicate,
+            items: vec![],
+            visibility: Visibility::default(),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl<S, C, P> SignalVec for FilterBySignal<S, C, P>
+where
+    S: SignalVec,
+    S::Item: Clone,
+    C: Signal,
+    P: FnMut(&C::Item, &S::Item) -> bool,
+{
+    type Item = S::Item;
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(diff) = this.pending.pop_front() {
+                return Poll::Ready(Some(diff));
+            }
+
+            let mut criteria_changed = false;
+            while let Some(Poll::Ready(next)) = this
+                .criteria_signal
+                .as_mut()
+                .as_pin_mut()
+                .map(|signal| signal.poll_change(cx))
+            {
+                match next {
+                    Some(criteria) => {
+                        *this.criteria = Some(criteria);
+                        criteria_changed = true;
+                    }
+                    None => this.criteria_signal.set(None),
+                }
+            }
+
+            let Some(criteria) = this.criteria.as_ref() else {
+                return if this.criteria_signal.is_none() {
+                    Poll::Ready(None)
+                } else {
+                    Poll::Pending
+                };
+            };
+
+            if criteria_changed {
+                let items = &*this.items;
+                this.visibility.reset(
+                    |index| (this.predicate)(criteria, &items[index]),
+                    |index| items[index].clone(),
+                    this.pending,
+                );
+                continue;
+            }
+
+            let diff = match this
+                .signal
+                .as_mut()
+                .as_pin_mut()
+                .map(|signal| signal.poll_vec_change(cx))
+            {
+                Some(Poll::Ready(Some(diff))) => diff,
+                Some(Poll::Ready(None)) => {
+                    this.signal.set(None);
+                    continue;
+                }
+                Some(Poll::Pending) => return Poll::Pending,
+                None if this.criteria_signal.is_none() => return Poll::Ready(None),
+                None => return Poll::Pending,
+            };
+
+            let mut visible = |item: &S::Item| (this.predicate)(criteria, item);
+            match diff {
+                VecDiff::Replace { values } => {
+                    this.visibility.replace(
+                        values.iter().map(|item| (visible(item), item.clone())),
+                        this.pending,
+                    );
+                    *this.items = values;
+                }
+                VecDiff::InsertAt { index, value } => {
+                    this.visibility
+                        .insert(index, visible(&value), value.clone(), this.pending);
+                    this.items.insert(index, value);
+                }
+                VecDiff::UpdateAt { index, value } => {
+                    this.visibility
+                        .update(index, visible(&value), value.clone(), this.pending);
+                    this.items[index] = value;
+                }
+                VecDiff::RemoveAt { index } => {
+                    this.visibility.remove(index, this.pending);
+                    this.items.remove(index);
+                }
+                VecDiff::Move {
+                    old_index,
+                    new_index,
+                } => {
+                    this.visibility
+                        .move_item(old_index, new_index, this.pending);
+                    let item = this.items.remove(old_index);
+                    this.items.insert(new_index, item);
+                }
+                VecDiff::Push { value } => {
+                    let index = this.items.len();
+                    this.visibility
+                        .insert(index, visible(&value), value.clone(), this.pending);
+                    this.items.push(value);
+                }
+                VecDiff::Pop {} => {
+                    this.visibility.remove(this.items.len() - 1, this.pending);
+                    this.items.pop();
+                }
+                VecDiff::Clear {} => {
+                    this.visibility.clear(this.pending);
+                    this.items.clear();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::pin::pin;
+
+    use futures_signals::{
+        signal::Mutable,
+        signal_vec::{MutableVec, VecDiff},
+    };
+
+    use crate::{MutableVecExt, testing::poll_vec};
+
+    #[test]
+    fn filter_by_signal() {
+        let vec = MutableVec::new_with_values(vec![1, 2, 3, 4]);
+        let min = Mutable::new(3);
+        let mut filtered =
+            pin!(vec.signal_vec_filter_by_signal(min.signal(), |min, item| item >= min));
+        assert_eq!(
+            poll_vec(filtered.as_mut()),
+            vec![VecDiff::Replace { values: vec![3, 4] }]
+        );
+
+        vec.lock_mut().push(0);
+        vec.lock_mut().set(0, 5);
+        assert_eq!(
+            poll_vec(filtered.as_mut()),
+            vec![VecDiff::InsertAt { index: 0, value: 5 }]
+        );
+
+        min.set(2);
+        assert_eq!(
+            poll_vec(filtered.as_mut()),
+            vec![VecDiff::InsertAt { index: 1, value: 2 }]
+        );
+
+        min.set(4);
+        assert_eq!(
+            poll_vec(filtered.as_mut()),
+            vec![
+                VecDiff::RemoveAt { index: 1 },
+                VecDiff::RemoveAt { index: 1 }
+            ]
+        );
+    }
+}
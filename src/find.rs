@@ -0,0 +1,359 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_signals::{signal::Signal, signal_vec::SignalVec};
+use pin_project_lite::pin_project;
+
+use crate::{Aggregate, Aggregator, aggregate::Emitted, sequence::Sequence};
+
+// Index of the first matching item, matching items weigh 1, so the first one
+// is found in O(log n) after any change.
+#[derive(Debug)]
+struct Found<U> {
+    values: Sequence<Option<U>>,
+}
+
+impl<U> Default for Found<U> {
+    fn default() -> Self {
+        Self {
+            values: Sequence::default(),
+        }
+    }
+}
+
+impl<U> Found<U> {
+    fn index(&self) -> Option<usize> {
+        self.values.first_weighted()
+    }
+
+    fn value(&self) -> Option<&U> {
+        self.index()
+            .and_then(|index| self.values.get(index).as_ref())
+    }
+
+    fn insert(&mut self, index: usize, value: Option<U>) {
+        let weight = usize::from(value.is_some());
+        self.values.insert(index, value, weight);
+    }
+
+    fn update(&mut self, index: usize, value: Option<U>) {
+        self.values.set_weight(index, usize::from(value.is_some()));
+        *self.values.get_mut(index) = value;
+    }
+
+    fn remove(&mut self, index: usize) {
+        self.values.remove(index);
+    }
+
+    fn move_item(&mut self, old_index: usize, new_index: usize) {
+        self.values.move_item(old_index, new_index);
+    }
+
+    fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+#[derive(Debug)]
+pub struct FindMap<F, U> {
+    f: F,
+    found: Found<U>,
+    emitted: Emitted<Option<U>>,
+}
+
+impl<F, U> FindMap<F, U> {
+    pub(crate) fn new(f: F) -> Self {
+        Self {
+            f,
+            found: Found::default(),
+            emitted: Emitted::default(),
+        }
+    }
+}
+
+impl<A, F, U> Aggregator<A> for FindMap<F, U>
+where
+    F: FnMut(&A) -> Option<U>,
+    U: PartialEq + Clone,
+{
+    type Output = Option<U>;
+
+    fn insert(&mut self, index: usize, item: A) {
+        self.found.insert(index, (self.f)(&item));
+    }
+
+    fn update(&mut self, index: usize, item: A) {
+        self.found.update(index, (self.f)(&item));
+    }
+
+    fn remove(&mut self, index: usize) {
+        self.found.remove(index);
+    }
+
+    fn move_item(&mut self, old_index: usize, new_index: usize) {
+        self.found.move_item(old_index, new_index);
+    }
+
+    fn clear(&mut self) {
+        self.found.clear();
+    }
+
+    fn changed(&mut self) -> Option<Option<U>> {
+        self.emitted.emit(self.found.value().cloned())
+    }
+}
+
+#[derive(Debug)]
+pub struct Position<F> {
+    f: F,
+    found: Found<()>,
+    emitted: Emitted<Option<usize>>,
+}
+
+impl<F> Position<F> {
+    pub(crate) fn new(f: F) -> Self {
+        Self {
+            f,
+            found: Found::default(),
+            emitted: Emitted::default(),
+        }
+    }
+}
+
+impl<A, F> Aggregator<A> for Position<F>
+where
+    F: FnMut(&A) -> bool,
+{
+    type Output = Option<usize>;
+
+    fn insert(&mut self, index: usize, item: A) {
+        self.found.insert(index, (self.f)(&item).then_some(()));
+    }
+
+    fn update(&mut self, index: usize, item: A) {
+        self.found.update(index, (self.f)(&item).then_some(()));
+    }
+
+    fn remove(&mut self, index: usize) {
+        self.found.remove(index);
+    }
+
+    fn move_item(&mut self, old_index: usize, new_index: usize) {
+        self.found.move_item(old_index, new_index);
+    }
+
+    fn clear(&mut self) {
+        self.found.clear();
+    }
+
+    fn changed(&mut self) -> Option<Option<usize>> {
+        self.emitted.emit(self.found.index())
+    }
+}
+
+/// Emits only when the item at the index changes. All items are kept, as any of
+/// them can be shifted to the index, each diff is O(log n).
+#[derive(Debug)]
+pub struct Nth<A> {
+    items: Sequence<A>,
+    index: Option<usize>,
+    affected: bool,
+    emitted: Emitted<Option<A>>,
+}
+
+impl<A> Nth<A> {
+    pub(crate) fn new(index: Option<usize>) -> Self {
+        Self {
+            items: Sequence::default(),
+            index,
+            affected: true,
+            emitted: Emitted::default(),
+        }
+    }
+
+    fn set_index(&mut self, index: usize) {
+        if self.index != Some(index) {
+            self.index = Some(index);
+            self.affected = true;
+        }
+    }
+
+    // marks affected if the index is within the range of changed positions
+    fn touch(&mut self, from: usize, to: usize) {
+        if self.index.is_some_and(|index| from <= index && index <= to) {
+            self.affected = true;
+        }
+    }
+}
+
+impl<A: PartialEq + Clone> Aggregator<A> for Nth<A> {
+    type Output = Option<A>;
+
+    fn insert(&mut self, index: usize, item: A) {
+        self.items.insert(index, item, 0);
+        self.touch(index, self.items.len() - 1);
+    }
+
+    fn update(&mut self, index: usize, item: A) {
+        *self.items.get_mut(index) = item;
+        self.touch(index, index);
+    }
+
+    fn remove(&mut self, index: usize) {
+        self.touch(index, self.items.len() - 1);
+        self.items.remove(index);
+    }
+
+    fn move_item(&mut self, old_index: usize, new_index: usize) {
+        self.items.move_item(old_index, new_index);
+        self.touch(old_index.min(new_index), old_index.max(new_index));
+    }
+
+    fn clear(&mut self) {
+        if self.items.len() > 0 {
+            self.touch(0, self.items.len() - 1);
+            self.items.clear();
+        }
+    }
+
+    fn changed(&mut self) -> Option<Option<A>> {
+        let index = self.index?;
+        if !self.affected {
+            return None;
+        }
+        self.affected = false;
+        let item = (index < self.items.len()).then(|| self.items.get(index).clone());
+        self.emitted.emit(item)
+    }
+}
+
+pin_project! {
+    /// Item at the index given by a signal.
+    #[derive(Debug)]
+    #[must_use = "Signals do nothing unless polled"]
+    pub struct NthSignal<S, I>
+    where
+        S: SignalVec,
+    {
+        #[pin]
+        index: Option<I>,
+        #[pin]
+        aggregate: Aggregate<S, Nth<S::Item>>,
+    }
+}
+
+impl<S: SignalVec, I> NthSignal<S, I> {
+    pub(crate) fn new(signal: S, index: I) -> Self {
+        Self {
+            index: Some(index),
+            aggregate: Aggregate::new(signal, Nth::new(None)),
+        }
+    }
+}
+
+impl<S, I> Signal for NthSignal<S, I>
+where
+    S: SignalVec,
+    S::Item: PartialEq + Clone,
+    I: Signal<Item = usize>,
+{
+    type Item = Option<S::Item>;
+
+    fn poll_change(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        let index_done = loop {
+            break match this
+                .index
+                .as_mut()
+                .as_pin_mut()
+                .map(|signal| signal.poll_change(cx))
+            {
+                Some(Poll::Ready(Some(index))) => {
+                    this.aggregate.as_mut().aggregator_mut().set_index(index);
+                    continue;
+                }
+                Some(Poll::Ready(None)) => {
+                    this.index.set(None);
+                    true
+                }
+                Some(Poll::Pending) => false,
+                None => true,
+            };
+        };
+
+        match this.aggregate.poll_change(cx) {
+            Poll::Ready(Some(item)) => Poll::Ready(Some(item)),
+            Poll::Ready(None) if index_done => Poll::Ready(None),
+            _ => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::pin::pin;
+
+    use futures_signals::{signal::Mutable, signal_vec::MutableVec};
+
+    use crate::{SignalVecFinalizerExt, testing::poll_signal};
+
+    #[test]
+    fn positional() {
+        let vec = MutableVec::new_with_values(vec![1, 2, 3, 4]);
+        let index = Mutable::new(0);
+        let mut nth = pin!(vec.signal_vec().nth(2));
+        let mut nth_signal = pin!(vec.signal_vec().nth_signal(index.signal()));
+        let mut position = pin!(vec.signal_vec().position(|item| item % 2 == 0));
+        let mut find = pin!(vec.signal_vec().find(|item| *item > 2));
+        let mut contains = pin!(vec.signal_vec().contains(5));
+        assert_eq!(poll_signal(nth.as_mut()), vec![Some(3)]);
+        assert_eq!(poll_signal(nth_signal.as_mut()), vec![Some(1)]);
+        assert_eq!(poll_signal(position.as_mut()), vec![Some(1)]);
+        assert_eq!(poll_signal(find.as_mut()), vec![Some(3)]);
+        assert_eq!(poll_signal(contains.as_mut()), vec![false]);
+
+        vec.lock_mut().set(3, 5);
+        assert_eq!(poll_signal(nth.as_mut()), vec![]);
+        assert_eq!(poll_signal(nth_signal.as_mut()), vec![]);
+        assert_eq!(poll_signal(position.as_mut()), vec![]);
+        assert_eq!(poll_signal(find.as_mut()), vec![]);
+        assert_eq!(poll_signal(contains.as_mut()), vec![true]);
+
+        vec.lock_mut().remove(1);
+        index.set(3);
+        assert_eq!(poll_signal(nth.as_mut()), vec![Some(5)]);
+        assert_eq!(poll_signal(nth_signal.as_mut()), vec![None]);
+        assert_eq!(poll_signal(position.as_mut()), vec![None]);
+        assert_eq!(poll_signal(find.as_mut()), vec![]);
+
+        vec.lock_mut().insert(0, 4);
+        vec.lock_mut().move_from_to(3, 1);
+        assert_eq!(vec.lock_ref().as_slice(), &[4, 5, 1, 3]);
+        assert_eq!(poll_signal(nth_signal.as_mut()), vec![Some(3)]);
+        assert_eq!(poll_signal(position.as_mut()), vec![Some(0)]);
+        assert_eq!(poll_signal(find.as_mut()), vec![Some(4)]);
+    }
+
+    #[test]
+    fn nth_unchanged() {
+        let vec = MutableVec::new_with_values(vec![1, 2]);
+        let mut nth = pin!(vec.signal_vec().nth(1));
+        assert_eq!(poll_signal(nth.as_mut()), vec![Some(2)]);
+
+        // equal item shifted to or set at the index
+        vec.lock_mut().insert(0, 2);
+        vec.lock_mut().remove(0);
+        vec.lock_mut().set(1, 2);
+        assert_eq!(poll_signal(nth.as_mut()), vec![]);
+
+        vec.lock_mut().insert(1, 2);
+        assert_eq!(poll_signal(nth.as_mut()), vec![]);
+        vec.lock_mut().remove(0);
+        assert_eq!(poll_signal(nth.as_mut()), vec![]);
+        vec.lock_mut().pop();
+        assert_eq!(poll_signal(nth.as_mut()), vec![None]);
+    }
+}
@@ -1,5 +1,6 @@
 use std::{
     collections::VecDeque,
+    iter,
     pin::Pin,
     task::{Context, Poll},
 };
@@ -7,6 +8,8 @@ use std::{
 use futures_signals::signal_vec::{SignalVec, SignalVecExt, VecDiff};
 use pin_project_lite::pin_project;
 
+use crate::sequence::Sequence;
+
 pub(crate) struct FlattenState<A> {
     signal_vec: Option<Pin<Box<A>>>,
     len: usize,
@@ -73,6 +76,7 @@ where
         &mut self,
         cx: &mut Context,
         prev_len: usize,
+        direct: bool,
         pending: &mut PendingBuilder<VecDiff<A::Item>>,
     ) -> bool {
         loop {
@@ -83,6 +87,10 @@ where
                     self.update_len(&diff);
 
                     match diff {
+                        VecDiff::Replace { values } if direct => {
+                            pending.push(VecDiff::Replace { values });
+                        }
+                        VecDiff::Clear {} if direct => pending.push(VecDiff::Clear {}),
                         VecDiff::Replace { values } => {
                             for index in (0..old_len).rev() {
                                 pending.push(VecDiff::RemoveAt {
@@ -165,20 +173,23 @@ pin_project! {
     {
         #[pin]
         pub(crate) signal: Option<A>,
-        pub(crate) inner: Vec<FlattenState<A::Item>>,
+        // inner vecs weighted by their lengths, offsets are prefix sums
+        pub(crate) inner: Sequence<FlattenState<A::Item>>,
+        // inner Replace and Clear of the only inner vec are emitted as is
+        pub(crate) coalesce: bool,
         pub(crate) pending: VecDeque<VecDiff<<A::Item as SignalVec>::Item>>,
     }
 }
 
 fn fill_removals<A>(
-    inner: &[FlattenState<A>],
+    inner: &Sequence<FlattenState<A>>,
     index: usize,
     pending: &mut PendingBuilder<VecDiff<A::Item>>,
 ) where
     A: SignalVec,
 {
-    let removed_len = inner[index].len;
-    let prev_len: usize = inner[..index].iter().map(|state| state.len).sum();
+    let removed_len = inner.get(index).len;
+    let prev_len = inner.prefix(index);
     for index in (0..removed_len).rev() {
         pending.push(VecDiff::RemoveAt {
             index: prev_len + index,
@@ -187,17 +198,17 @@ fn fill_removals<A>(
 }
 
 fn fill_moves<A>(
-    inner: &[FlattenState<A>],
+    inner: &Sequence<FlattenState<A>>,
     old_index: usize,
     new_index: usize,
     pending: &mut PendingBuilder<VecDiff<A::Item>>,
 ) where
     A: SignalVec,
 {
-    let moved_len = inner[old_index].len;
-    let old_prev_len: usize = inner[..old_index].iter().map(|state| state.len).sum();
-    let new_prev_len: usize = inner[..new_index].iter().map(|state| state.len).sum();
+    let moved_len = inner.get(old_index).len;
+    let old_prev_len = inner.prefix(old_index);
     if new_index < old_index {
+        let new_prev_len = inner.prefix(new_index);
         (0..moved_len).for_each(|_| {
             pending.push(VecDiff::Move {
                 old_index: old_prev_len + moved_len - 1,
@@ -205,6 +216,8 @@ fn fill_moves<A>(
             })
         });
     } else {
+        // the moved items follow the items up to and including `new_index`
+        let new_prev_len = inner.prefix(new_index + 1) - moved_len;
         (0..moved_len).for_each(|_| {
             pending.push(VecDiff::Move {
                 old_index: old_prev_len,
@@ -214,6 +227,30 @@ fn fill_moves<A>(
     }
 }
 
+fn offset_diff<A>(diff: VecDiff<A>, offset: usize) -> VecDiff<A> {
+    match diff {
+        VecDiff::InsertAt { index, value } => VecDiff::InsertAt {
+            index: offset + index,
+            value,
+        },
+        VecDiff::UpdateAt { index, value } => VecDiff::UpdateAt {
+            index: offset + index,
+            value,
+        },
+        VecDiff::RemoveAt { index } => VecDiff::RemoveAt {
+            index: offset + index,
+        },
+        VecDiff::Move {
+            old_index,
+            new_index,
+        } => VecDiff::Move {
+            old_index: offset + old_index,
+            new_index: offset + new_index,
+        },
+        diff => diff,
+    }
+}
+
 impl<A> SignalVec for Flatten<A>
 where
     A: SignalVec,
@@ -243,25 +280,27 @@ where
                 Some(Poll::Ready(Some(diff))) => {
                     match diff {
                         VecDiff::Replace { values } => {
-                            *this.inner = values.into_iter().map(FlattenState::new).collect();
-
-                            let values = this
-                                .inner
-                                .iter_mut()
-                                .flat_map(|state| state.poll_values(cx))
-                                .collect();
+                            this.inner.clear();
+                            let mut output = vec![];
+                            for value in values {
+                                let mut state = FlattenState::new(value);
+                                output.extend(state.poll_values(cx));
+                                let len = state.len;
+                                this.inner.push(state, len);
+                            }
 
-                            return Poll::Ready(Some(VecDiff::Replace { values }));
+                            return Poll::Ready(Some(VecDiff::Replace { values: output }));
                         }
                         VecDiff::InsertAt { index, value } => {
-                            this.inner.insert(index, FlattenState::new(value));
+                            this.inner.insert(index, FlattenState::new(value), 0);
                         }
                         VecDiff::UpdateAt { index, value } => {
-                            fill_removals(&this.inner, index, &mut pending);
-                            this.inner[index] = FlattenState::new(value);
+                            fill_removals(this.inner, index, &mut pending);
+                            *this.inner.get_mut(index) = FlattenState::new(value);
+                            this.inner.set_weight(index, 0);
                         }
                         VecDiff::RemoveAt { index } => {
-                            fill_removals(&this.inner, index, &mut pending);
+                            fill_removals(this.inner, index, &mut pending);
                             this.inner.remove(index);
                         }
                         VecDiff::Move {
@@ -269,16 +308,15 @@ where
                             new_index,
                         } => {
                             if old_index != new_index {
-                                fill_moves(&this.inner, old_index, new_index, &mut pending);
-                                let value = this.inner.remove(old_index);
-                                this.inner.insert(new_index, value);
+                                fill_moves(this.inner, old_index, new_index, &mut pending);
+                                this.inner.move_item(old_index, new_index);
                             }
                         }
                         VecDiff::Push { value } => {
-                            this.inner.push(FlattenState::new(value));
+                            this.inner.push(FlattenState::new(value), 0);
                         }
                         VecDiff::Pop {} => {
-                            let len = this.inner.pop().unwrap().len;
+                            let len = this.inner.remove(this.inner.len() - 1).len;
                             (0..len).for_each(|_| pending.push(VecDiff::Pop {}));
                         }
                         VecDiff::Clear {} => {
@@ -299,10 +337,25 @@ where
         };
 
         let mut inner_done = true;
-        let mut prev_len = 0;
-        for state in this.inner.iter_mut() {
-            inner_done &= state.poll_pending(cx, prev_len, &mut pending);
-            prev_len += state.len;
+        let direct = *this.coalesce && this.inner.len() == 1;
+        for handle in this.inner.handles() {
+            let state = this.inner.value_mut(handle);
+            let old_len = state.len;
+            let mut diffs = PendingBuilder::new();
+            inner_done &= state.poll_pending(cx, 0, direct, &mut diffs);
+            let len = state.len;
+
+            // offsets and lengths are looked up only for inner vecs with changes
+            if let Some(first) = diffs.first {
+                let index = this.inner.index_of(handle);
+                let prev_len = this.inner.prefix(index);
+                for diff in iter::once(first).chain(diffs.rest) {
+                    pending.push(offset_diff(diff, prev_len));
+                }
+                if len != old_len {
+                    this.inner.set_weight(index, len);
+                }
+            }
         }
 
         if let Some(first) = pending.first {
@@ -316,6 +369,189 @@ where
     }
 }
 
+pin_project! {
+    /// Flattened diffs of one poll are emitted as a single `Replace` when there are
+    /// more than `threshold` of them.
+    #[must_use = "SignalVecs do nothing unless polled"]
+    pub struct FlattenCoalesced<A>
+    where
+        A: SignalVec,
+        A::Item: SignalVec,
+    {
+        #[pin]
+        flatten: Option<Flatten<A>>,
+        threshold: usize,
+        values: Vec<<A::Item as SignalVec>::Item>,
+        pending: VecDeque<VecDiff<<A::Item as SignalVec>::Item>>,
+    }
+}
+
+impl<A> FlattenCoalesced<A>
+where
+    A: SignalVec,
+    A::Item: SignalVec,
+{
+    pub(crate) fn new(signal: A, threshold: usize) -> Self {
+        Self {
+            flatten: Some(Flatten {
+                signal: Some(signal),
+                inner: Default::default(),
+                coalesce: true,
+                pending: VecDeque::new(),
+            }),
+            threshold,
+            values: vec![],
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl<A> SignalVec for FlattenCoalesced<A>
+where
+    A: SignalVec,
+    A::Item: SignalVec,
+    <A::Item as SignalVec>::Item: Clone,
+{
+    type Item = <A::Item as SignalVec>::Item;
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let mut this = self.project();
+
+        if let Some(diff) = this.pending.pop_front() {
+            return Poll::Ready(Some(diff));
+        }
+
+        // diffs are kept, and so cloned, only up to the threshold
+        let mut diffs = Some(vec![]);
+        while let Some(poll) = this
+            .flatten
+            .as_mut()
+            .as_pin_mut()
+            .map(|flatten| flatten.poll_vec_change(cx))
+        {
+            match poll {
+                Poll::Ready(Some(diff)) => {
+                    if let Some(kept) = &mut diffs {
+                        if kept.len() < *this.threshold {
+                            kept.push(diff.clone());
+                        } else {
+                            diffs = None;
+                        }
+                    }
+                    diff.apply_to_vec(this.values);
+                }
+                Poll::Ready(None) => this.flatten.set(None),
+                Poll::Pending => break,
+            }
+        }
+
+        match diffs {
+            Some(diffs) => this.pending.extend(diffs),
+            None => this.pending.push_back(VecDiff::Replace {
+                values: this.values.clone(),
+            }),
+        }
+
+        match this.pending.pop_front() {
+            Some(diff) => Poll::Ready(Some(diff)),
+            None if this.flatten.is_none() => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
+
+/// Items of the first signal vec followed by items of the second, see also
+/// `concat_all!`.
+#[must_use = "SignalVecs do nothing unless polled"]
+pub struct Concat<A, B>
+where
+    A: SignalVec,
+    B: SignalVec<Item = A::Item>,
+{
+    first: FlattenState<A>,
+    second: FlattenState<B>,
+    started: bool,
+    pending: VecDeque<VecDiff<A::Item>>,
+}
+
+impl<A, B> Concat<A, B>
+where
+    A: SignalVec,
+    B: SignalVec<Item = A::Item>,
+{
+    pub(crate) fn new(first: A, second: B) -> Self {
+        Self {
+            first: FlattenState::new(first),
+            second: FlattenState::new(second),
+            started: false,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+// items are never pinned
+impl<A, B> Unpin for Concat<A, B>
+where
+    A: SignalVec,
+    B: SignalVec<Item = A::Item>,
+{
+}
+
+impl<A, B> SignalVec for Concat<A, B>
+where
+    A: SignalVec,
+    B: SignalVec<Item = A::Item>,
+{
+    type Item = A::Item;
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let this = self.get_mut();
+
+        if let Some(diff) = this.pending.pop_front() {
+            return Poll::Ready(Some(diff));
+        }
+
+        if !this.started {
+            this.started = true;
+            let mut values = this.first.poll_values(cx);
+            values.extend(this.second.poll_values(cx));
+            return Poll::Ready(Some(VecDiff::Replace { values }));
+        }
+
+        let mut pending = PendingBuilder::new();
+        let first_done = this.first.poll_pending(cx, 0, false, &mut pending);
+        let second_done = this
+            .second
+            .poll_pending(cx, this.first.len, false, &mut pending);
+
+        if let Some(first) = pending.first {
+            this.pending = pending.rest;
+            Poll::Ready(Some(first))
+        } else if first_done && second_done {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Concatenates signal vecs of the same item type, `concat_all!(a, b, c)` is
+/// `a.concat(b).concat(c)`.
+#[macro_export]
+macro_rules! concat_all {
+    ($first:expr $(, $rest:expr)* $(,)?) => {{
+        let signal = $first;
+        $(let signal = $crate::SignalVecFlattenExt::concat(signal, $rest);)*
+        signal
+    }};
+}
+
 struct PendingBuilder<A> {
     first: Option<A>,
     rest: VecDeque<A>,
@@ -337,3 +573,201 @@ impl<A> PendingBuilder<A> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::pin::pin;
+
+    use futures_signals::signal_vec::{MutableVec, SignalVecExt, VecDiff};
+
+    use crate::{SignalVecFlattenExt, testing::poll_vec};
+
+    #[test]
+    fn flatten_matches_reference() {
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let mut random = move |bound: usize| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize % bound
+        };
+
+        let outer = MutableVec::new_with_values(
+            (0..8)
+                .map(|item| MutableVec::new_with_values(vec![item; item as usize % 3]))
+                .collect(),
+        );
+        let mut flatten = pin!(
+            outer
+                .signal_vec_cloned()
+                .map(|inner| inner.signal_vec())
+                .flatten_ext()
+        );
+        let mut output = vec![];
+        let mut next = 100;
+        for round in 0..500 {
+            for _ in 0..random(4) + 1 {
+                next += 1;
+                let len = outer.lock_ref().len();
+                let index = random(len.max(1));
+                match random(10) {
+                    0 if len > 0 => {
+                        outer.lock_mut().remove(index);
+                    }
+                    1 => outer.lock_mut().insert_cloned(
+                        random(len + 1),
+                        MutableVec::new_with_values(vec![next; random(4)]),
+                    ),
+                    2 if len > 0 => outer.lock_mut().move_from_to(index, random(len)),
+                    3 if len > 0 => outer
+                        .lock_mut()
+                        .set_cloned(index, MutableVec::new_with_values(vec![next; random(4)])),
+                    4 if len > 0 => {
+                        outer.lock_mut().pop();
+                    }
+                    _ if len > 0 => {
+                        let inner = outer.lock_ref()[index].clone();
+                        let mut inner = inner.lock_mut();
+                        let len = inner.len();
+                        let index = random(len.max(1));
+                        match random(7) {
+                            0 => inner.insert(random(len + 1), next),
+                            1 if len > 0 => {
+                                inner.remove(index);
+                            }
+                            2 if len > 0 => inner.set(index, next),
+                            3 if len > 0 => inner.move_from_to(index, random(len)),
+                            4 => inner.clear(),
+                            5 => inner.replace(vec![next; random(4)]),
+                            _ => inner.push(next),
+                        }
+                    }
+                    _ => outer
+                        .lock_mut()
+                        .push_cloned(MutableVec::new_with_values(vec![next])),
+                }
+            }
+
+            for diff in poll_vec(flatten.as_mut()) {
+                diff.apply_to_vec(&mut output);
+            }
+            let expected = outer
+                .lock_ref()
+                .iter()
+                .flat_map(|inner| inner.lock_ref().to_vec())
+                .collect::<Vec<_>>();
+            assert_eq!(output, expected, "round {round}");
+        }
+    }
+
+    #[test]
+    fn coalesced_direct() {
+        let first = MutableVec::new_with_values(vec![1, 2, 3]);
+        let outer = MutableVec::new_with_values(vec![first.clone()]);
+        let mut flatten = pin!(
+            outer
+                .signal_vec_cloned()
+                .map(|inner| inner.signal_vec())
+                .flatten_coalesced(10)
+        );
+        poll_vec(flatten.as_mut());
+
+        // Replace and Clear of the only inner vec are emitted as is
+        first.lock_mut().replace(vec![4, 5]);
+        first.lock_mut().clear();
+        first.lock_mut().replace(vec![6]);
+        assert_eq!(
+            poll_vec(flatten.as_mut()),
+            vec![
+                VecDiff::Replace { values: vec![4, 5] },
+                VecDiff::Clear {},
+                VecDiff::Replace { values: vec![6] }
+            ]
+        );
+
+        // but not with more inner vecs
+        outer.lock_mut().push_cloned(MutableVec::new());
+        poll_vec(flatten.as_mut());
+        first.lock_mut().replace(vec![7, 8]);
+        first.lock_mut().clear();
+        assert_eq!(
+            poll_vec(flatten.as_mut()),
+            vec![
+                VecDiff::RemoveAt { index: 0 },
+                VecDiff::InsertAt { index: 0, value: 7 },
+                VecDiff::InsertAt { index: 1, value: 8 },
+                VecDiff::RemoveAt { index: 1 },
+                VecDiff::RemoveAt { index: 0 }
+            ]
+        );
+    }
+
+    #[test]
+    fn flatten_coalesced() {
+        let first = MutableVec::new_with_values(vec![1, 2, 3]);
+        let outer = MutableVec::new_with_values(vec![first.clone()]);
+        let mut flatten = pin!(
+            outer
+                .signal_vec_cloned()
+                .map(|inner| inner.signal_vec())
+                .flatten_coalesced(2)
+        );
+        assert_eq!(
+            poll_vec(flatten.as_mut()),
+            vec![VecDiff::Replace {
+                values: vec![1, 2, 3]
+            }]
+        );
+
+        first.lock_mut().replace(vec![4, 5]);
+        first.lock_mut().clear();
+        poll_vec(flatten.as_mut());
+
+        outer
+            .lock_mut()
+            .push_cloned(MutableVec::new_with_values(vec![6, 7]));
+        first.lock_mut().push(8);
+        assert_eq!(
+            poll_vec(flatten.as_mut()),
+            vec![VecDiff::Replace {
+                values: vec![8, 6, 7]
+            }]
+        );
+
+        first.lock_mut().pop();
+        assert_eq!(
+            poll_vec(flatten.as_mut()),
+            vec![VecDiff::RemoveAt { index: 0 }]
+        );
+    }
+
+    #[test]
+    fn concat_all() {
+        let a = MutableVec::new_with_values(vec![1, 2]);
+        let b = MutableVec::new_with_values(vec![3]);
+        let c = MutableVec::new_with_values(vec!["4"]);
+        let mut concat = pin!(concat_all!(
+            a.signal_vec(),
+            b.signal_vec(),
+            c.signal_vec().map(|item| item.parse().unwrap()),
+        ));
+        assert_eq!(
+            poll_vec(concat.as_mut()),
+            vec![VecDiff::Replace {
+                values: vec![1, 2, 3, 4]
+            }]
+        );
+
+        b.lock_mut().insert(0, 5);
+        c.lock_mut().clear();
+        a.lock_mut().pop();
+        assert_eq!(
+            poll_vec(concat.as_mut()),
+            vec![
+                VecDiff::RemoveAt { index: 1 },
+                VecDiff::InsertAt { index: 1, value: 5 },
+                VecDiff::RemoveAt { index: 3 }
+            ]
+        );
+    }
+}
@@ -0,0 +1,328 @@
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_signals::signal_vec::{SignalVec, VecDiff};
+use pin_project_lite::pin_project;
+
+use crate::Flatten;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FlatItem<H, T, F> {
+    Header(H),
+    Item(T),
+    Footer(F),
+}
+
+pub type FlattenWith<A, FH, FF> = Flatten<Frames<A, FH, FF>>;
+
+// Header and footer of a group with the number of its items.
+#[derive(Debug)]
+struct Frame<H, F> {
+    header: H,
+    footer: F,
+    hide_empty: bool,
+    len: usize,
+    started: bool,
+}
+
+impl<H: Clone, F: Clone> Frame<H, F> {
+    fn shown(&self) -> bool {
+        !self.hide_empty || self.len > 0
+    }
+
+    fn values<T>(&self, items: Vec<T>) -> Vec<FlatItem<H, T, F>> {
+        if !self.shown() {
+            return vec![];
+        }
+
+        let mut values = Vec::with_capacity(items.len() + 2);
+        values.push(FlatItem::Header(self.header.clone()));
+        values.extend(items.into_iter().map(FlatItem::Item));
+        values.push(FlatItem::Footer(self.footer.clone()));
+        values
+    }
+
+    fn insert<T>(&mut self, index: usize, value: T, pending: &mut Pending<H, T, F>) {
+        let was_shown = self.shown();
+        self.len += 1;
+        if was_shown {
+            pending.push_back(VecDiff::InsertAt {
+                index: index + 1,
+                value: FlatItem::Item(value),
+            });
+        } else {
+            let values = self.values(vec![value]).into_iter().enumerate();
+            pending.extend(values.map(|(index, value)| VecDiff::InsertAt { index, value }));
+        }
+    }
+
+    fn remove<T>(&mut self, index: usize, pending: &mut Pending<H, T, F>) {
+        self.len -= 1;
+        if self.shown() {
+            pending.push_back(VecDiff::RemoveAt { index: index + 1 });
+        } else {
+            // the item with header and footer
+            for index in (0..3).rev() {
+                pending.push_back(VecDiff::RemoveAt { index });
+            }
+        }
+    }
+}
+
+type Pending<H, T, F> = VecDeque<VecDiff<FlatItem<H, T, F>>>;
+
+pin_project! {
+    /// Items of a group between its header and footer. With `hide_empty` an empty
+    /// group emits no header and footer.
+    #[derive(Debug)]
+    #[must_use = "SignalVecs do nothing unless polled"]
+    pub struct Framed<S, H, F>
+    where
+        S: SignalVec,
+    {
+        #[pin]
+        signal: Option<S>,
+        frame: Frame<H, F>,
+        pending: Pending<H, S::Item, F>,
+    }
+}
+
+impl<S, H, F> SignalVec for Framed<S, H, F>
+where
+    S: SignalVec,
+    H: Clone,
+    F: Clone,
+{
+    type Item = FlatItem<H, S::Item, F>;
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(diff) = this.pending.pop_front() {
+                return Poll::Ready(Some(diff));
+            }
+
+            let poll = this
+                .signal
+                .as_mut()
+                .as_pin_mut()
+                .map(|signal| signal.poll_vec_change(cx));
+
+            // a source starting empty may emit nothing
+            let frame = &mut *this.frame;
+            if !frame.started {
+                frame.started = true;
+                let replaced = matches!(poll, Some(Poll::Ready(Some(VecDiff::Replace { .. }))));
+                if !replaced && frame.shown() {
+                    let values = frame.values(vec![]);
+                    this.pending.push_back(VecDiff::Replace { values });
+                }
+            }
+
+            let diff = match poll {
+                Some(Poll::Ready(Some(diff))) => diff,
+                Some(Poll::Ready(None)) => {
+                    this.signal.set(None);
+                    continue;
+                }
+                _ if !this.pending.is_empty() => continue,
+                Some(Poll::Pending) => return Poll::Pending,
+                None => return Poll::Ready(None),
+            };
+
+            match diff {
+                VecDiff::Replace { values } => {
+                    frame.len = values.len();
+                    let values = frame.values(values);
+                    this.pending.push_back(VecDiff::Replace { values });
+                }
+                VecDiff::InsertAt { index, value } => frame.insert(index, value, this.pending),
+                VecDiff::UpdateAt { index, value } => {
+                    this.pending.push_back(VecDiff::UpdateAt {
+                        index: index + 1,
+                        value: FlatItem::Item(value),
+                    });
+                }
+                VecDiff::RemoveAt { index } => frame.remove(index, this.pending),
+                VecDiff::Move {
+                    old_index,
+                    new_index,
+                } => {
+                    this.pending.push_back(VecDiff::Move {
+                        old_index: old_index + 1,
+                        new_index: new_index + 1,
+                    });
+                }
+                VecDiff::Push { value } => frame.insert(frame.len, value, this.pending),
+                VecDiff::Pop {} => frame.remove(frame.len - 1, this.pending),
+                VecDiff::Clear {} => {
+                    let was_shown = frame.shown();
+                    frame.len = 0;
+                    if frame.shown() {
+                        let values = frame.values(vec![]);
+                        this.pending.push_back(VecDiff::Replace { values });
+                    } else if was_shown {
+                        this.pending.push_back(VecDiff::Clear {});
+                    }
+                }
+            }
+        }
+    }
+}
+
+pin_project! {
+    /// Groups mapped to their framed items, see `SignalVecFlattenExt::flatten_with`.
+    #[derive(Debug)]
+    #[must_use = "SignalVecs do nothing unless polled"]
+    pub struct Frames<A, FH, FF> {
+        #[pin]
+        signal: A,
+        header: FH,
+        footer: FF,
+        hide_empty: bool,
+    }
+}
+
+impl<A, FH, FF> Frames<A, FH, FF> {
+    pub(crate) fn new(signal: A, header: FH, footer: FF, hide_empty: bool) -> Self {
+        Self {
+            signal,
+            header,
+            footer,
+            hide_empty,
+        }
+    }
+}
+
+impl<A, G, S, FH, FF, H, F> SignalVec for Frames<A, FH, FF>
+where
+    A: SignalVec<Item = (G, S)>,
+    S: SignalVec,
+    FH: FnMut(&G) -> H,
+    FF: FnMut(&G) -> F,
+{
+    type Item = Framed<S, H, F>;
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let this = self.project();
+        let mut frame = |(group, signal): (G, S)| Framed {
+            signal: Some(signal),
+            frame: Frame {
+                header: (this.header)(&group),
+                footer: (this.footer)(&group),
+                hide_empty: *this.hide_empty,
+                len: 0,
+                started: false,
+            },
+            pending: VecDeque::new(),
+        };
+
+        this.signal.poll_vec_change(cx).map(|diff| {
+            diff.map(|diff| match diff {
+                VecDiff::Replace { values } => VecDiff::Replace {
+                    values: values.into_iter().map(&mut frame).collect(),
+                },
+                VecDiff::InsertAt { index, value } => VecDiff::InsertAt {
+                    index,
+                    value: frame(value),
+                },
+                VecDiff::UpdateAt { index, value } => VecDiff::UpdateAt {
+                    index,
+                    value: frame(value),
+                },
+                VecDiff::RemoveAt { index } => VecDiff::RemoveAt { index },
+                VecDiff::Move {
+                    old_index,
+                    new_index,
+                } => VecDiff::Move {
+                    old_index,
+                    new_index,
+                },
+                VecDiff::Push { value } => VecDiff::Push {
+                    value: frame(value),
+                },
+                VecDiff::Pop {} => VecDiff::Pop {},
+                VecDiff::Clear {} => VecDiff::Clear {},
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::pin::pin;
+
+    use futures_signals::signal_vec::{MutableVec, SignalVecExt};
+
+    use crate::{FlatItem, SignalVecFlattenExt, testing::poll_signal};
+
+    #[test]
+    fn flatten_with() {
+        let a = MutableVec::new_with_values(vec![1, 2]);
+        let b = MutableVec::new();
+        let groups = MutableVec::new_with_values(vec![('a', a.clone()), ('b', b.clone())]);
+        let flatten = |hide_empty| {
+            groups
+                .signal_vec_cloned()
+                .map(|(group, items)| (group, items.signal_vec()))
+                .flatten_with(
+                    |group| *group,
+                    |group| group.to_ascii_uppercase(),
+                    hide_empty,
+                )
+                .to_signal_cloned()
+        };
+        let mut shown = pin!(flatten(false));
+        let mut hidden = pin!(flatten(true));
+        use FlatItem::{Footer, Header, Item};
+        assert_eq!(
+            poll_signal(shown.as_mut()).pop(),
+            Some(vec![
+                Header('a'),
+                Item(1),
+                Item(2),
+                Footer('A'),
+                Header('b'),
+                Footer('B')
+            ])
+        );
+        assert_eq!(
+            poll_signal(hidden.as_mut()).pop(),
+            Some(vec![Header('a'), Item(1), Item(2), Footer('A')])
+        );
+
+        b.lock_mut().push(3);
+        a.lock_mut().clear();
+        assert_eq!(
+            poll_signal(shown.as_mut()).pop(),
+            Some(vec![
+                Header('a'),
+                Footer('A'),
+                Header('b'),
+                Item(3),
+                Footer('B')
+            ])
+        );
+        assert_eq!(
+            poll_signal(hidden.as_mut()).pop(),
+            Some(vec![Header('b'), Item(3), Footer('B')])
+        );
+
+        b.lock_mut().remove(0);
+        a.lock_mut().push(4);
+        assert_eq!(
+            poll_signal(hidden.as_mut()).pop(),
+            Some(vec![Header('a'), Item(4), Footer('A')])
+        );
+    }
+}
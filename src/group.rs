@@ -0,0 +1,566 @@
+use std::{
+    collections::VecDeque,
+    hash::Hash,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_signals::signal_vec::{MutableSignalVec, MutableVec, SignalVec, VecDiff};
+use pin_project_lite::pin_project;
+
+use crate::{HashMap, sequence::Sequence};
+
+#[derive(Debug)]
+pub(crate) struct Group<K, T> {
+    key: K,
+    vec: MutableVec<T>,
+    // source handles of the items, in the source order
+    members: Sequence<usize>,
+}
+
+// key of every source item with the handle of its group member
+type Items<K> = Sequence<(K, usize)>;
+
+#[derive(Debug)]
+pub(crate) struct Groups<K, T> {
+    groups: Sequence<Group<K, T>>,
+    // group handles by key, kept only if groups are ordered by appearance
+    handles: HashMap<K, usize>,
+}
+
+impl<K, T> Default for Groups<K, T> {
+    fn default() -> Self {
+        Self {
+            groups: Sequence::default(),
+            handles: HashMap::default(),
+        }
+    }
+}
+
+// Source index of the first item of the group.
+fn first<K, T>(items: &Items<K>, group: &Group<K, T>) -> usize {
+    items.index_of(*group.members.get(0))
+}
+
+// Order of the groups, decides how a group is found and where a new one goes.
+#[derive(Debug)]
+pub(crate) struct Locate<K, T> {
+    // handle of the group with the key
+    find: fn(&Groups<K, T>, &K) -> Option<usize>,
+    // index for a new group with the key, its first item being at the index
+    place: fn(&Groups<K, T>, &Items<K>, &K, usize) -> usize,
+    // records the handle of the group with the key, None if removed
+    index: fn(&mut Groups<K, T>, &K, Option<usize>),
+    // groups move when their first item changes
+    by_appearance: bool,
+}
+
+impl<K, T> Clone for Locate<K, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, T> Copy for Locate<K, T> {}
+
+impl<K: Eq + Hash + Clone, T> Locate<K, T> {
+    pub(crate) fn by_appearance() -> Self {
+        Self {
+            find: |groups, key| groups.handles.get(key).copied(),
+            place: |groups, items, _, index| {
+                groups
+                    .groups
+                    .partition_point(|group| first(items, group) < index)
+            },
+            index: |groups, key, handle| match handle {
+                Some(handle) => {
+                    groups.handles.insert(key.clone(), handle);
+                }
+                None => {
+                    groups.handles.remove(key);
+                }
+            },
+            by_appearance: true,
+        }
+    }
+}
+
+impl<K: Ord, T> Locate<K, T> {
+    pub(crate) fn by_key() -> Self {
+        Self {
+            find: |groups, key| {
+                let index = groups.groups.partition_point(|group| group.key < *key);
+                (index < groups.groups.len() && groups.groups.get(index).key == *key)
+                    .then(|| groups.groups.handle(index))
+            },
+            place: |groups, _, key, _| groups.groups.partition_point(|group| group.key < *key),
+            index: |_, _, _| {},
+            by_appearance: false,
+        }
+    }
+}
+
+pin_project! {
+    /// Groups of items with equal keys, items keep the source order within the group.
+    /// Empty groups are removed. Each diff is O(log² n).
+    #[derive(Debug)]
+    #[must_use = "SignalVecs do nothing unless polled"]
+    pub struct GroupBy<S, F, K>
+    where
+        S: SignalVec,
+    {
+        #[pin]
+        signal: Option<S>,
+        key: F,
+        locate: Locate<K, S::Item>,
+        items: Items<K>,
+        groups: Groups<K, S::Item>,
+        pending: VecDeque<VecDiff<(K, MutableSignalVec<S::Item>)>>,
+    }
+}
+
+impl<S: SignalVec, F, K> GroupBy<S, F, K> {
+    pub(crate) fn new(signal: S, key: F, locate: Locate<K, S::Item>) -> Self {
+        Self {
+            signal: Some(signal),
+            key,
+            locate,
+            items: Sequence::default(),
+            groups: Groups::default(),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+struct Grouping<'a, K, T, F> {
+    key: &'a mut F,
+    locate: Locate<K, T>,
+    items: &'a mut Items<K>,
+    groups: &'a mut Groups<K, T>,
+    pending: &'a mut VecDeque<VecDiff<(K, MutableSignalVec<T>)>>,
+}
+
+impl<K, T, F> Grouping<'_, K, T, F>
+where
+    K: PartialEq + Clone,
+    T: Clone,
+    F: FnMut(&T) -> K,
+{
+    fn group(&self, key: &K) -> usize {
+        (self.locate.find)(self.groups, key).expect("group of the item")
+    }
+
+    // position within the group for an item placed at the index
+    fn position_at(&self, group: usize, index: usize) -> usize {
+        let items = &*self.items;
+        self.groups
+            .groups
+            .value(group)
+            .members
+            .partition_point(|handle| items.index_of(*handle) < index)
+    }
+
+    // moves the group whose first item changed to its place
+    fn reorder(&mut self, handle: usize) {
+        if !self.locate.by_appearance {
+            return;
+        }
+
+        let groups = &mut self.groups.groups;
+        let old_index = groups.index_of(handle);
+        let group = groups.remove(old_index);
+        let index = first(self.items, &group);
+        let new_index = groups.partition_point(|group| first(self.items, group) < index);
+        let key = group.key.clone();
+        let handle = groups.insert(new_index, group, 0);
+        (self.locate.index)(self.groups, &key, Some(handle));
+        if old_index != new_index {
+            self.pending.push_back(VecDiff::Move {
+                old_index,
+                new_index,
+            });
+        }
+    }
+
+    fn insert(&mut self, index: usize, value: T) {
+        let key = (self.key)(&value);
+        let item = self.items.insert(index, (key.clone(), 0), 0);
+        match (self.locate.find)(self.groups, &key) {
+            Some(handle) => {
+                let position = self.position_at(handle, index);
+                let group = self.groups.groups.value_mut(handle);
+                self.items.get_mut(index).1 = group.members.insert(position, item, 0);
+                group.vec.lock_mut().insert_cloned(position, value);
+                if position == 0 {
+                    self.reorder(handle);
+                }
+            }
+            None => {
+                let mut members = Sequence::default();
+                self.items.get_mut(index).1 = members.insert(0, item, 0);
+                let vec = MutableVec::new_with_values(vec![value]);
+                let group = (self.locate.place)(self.groups, self.items, &key, index);
+                self.pending.push_back(VecDiff::InsertAt {
+                    index: group,
+                    value: (key.clone(), vec.signal_vec_cloned()),
+                });
+                let group = self.groups.groups.insert(
+                    group,
+                    Group {
+                        key: key.clone(),
+                        vec,
+                        members,
+                    },
+                    0,
+                );
+                (self.locate.index)(self.groups, &key, Some(group));
+            }
+        }
+    }
+
+    fn update(&mut self, index: usize, value: T) {
+        let key = (self.key)(&value);
+        let (old_key, item) = self.items.get(index);
+        if key == *old_key {
+            let item = *item;
+            let group = self.groups.groups.value(self.group(&key));
+            let position = group.members.index_of(item);
+            group.vec.lock_mut().set_cloned(position, value);
+        } else {
+            self.remove(index);
+            self.insert(index, value);
+        }
+    }
+
+    fn remove(&mut self, index: usize) {
+        let (key, item) = self.items.remove(index);
+        let handle = self.group(&key);
+        let group = self.groups.groups.value_mut(handle);
+        let position = group.members.index_of(item);
+        group.members.remove(position);
+        group.vec.lock_mut().remove(position);
+        if group.members.len() == 0 {
+            let index = self.groups.groups.index_of(handle);
+            self.groups.groups.remove(index);
+            (self.locate.index)(self.groups, &key, None);
+            self.pending.push_back(VecDiff::RemoveAt { index });
+        } else if position == 0 {
+            self.reorder(handle);
+        }
+    }
+
+    fn move_item(&mut self, old_index: usize, new_index: usize) {
+        let item = self.items.get(old_index).1;
+        let handle = self.group(&self.items.get(old_index).0);
+        let old_position = self.groups.groups.value(handle).members.index_of(item);
+        self.items.move_item(old_index, new_index);
+        let item = self
+            .groups
+            .groups
+            .value_mut(handle)
+            .members
+            .remove(old_position);
+        let new_position = self.position_at(handle, new_index);
+        let group = self.groups.groups.value_mut(handle);
+        self.items.get_mut(new_index).1 = group.members.insert(new_position, item, 0);
+        if old_position != new_position {
+            group
+                .vec
+                .lock_mut()
+                .move_from_to(old_position, new_position);
+        }
+        if old_position == 0 || new_position == 0 {
+            self.reorder(handle);
+        }
+    }
+
+    fn replace(&mut self, values: Vec<T>) {
+        self.reset();
+        for value in values {
+            self.insert(self.items.len(), value);
+        }
+        self.pending.clear();
+        let groups = &self.groups.groups;
+        self.pending.push_back(VecDiff::Replace {
+            values: (0..groups.len())
+                .map(|index| {
+                    let group = groups.get(index);
+                    (group.key.clone(), group.vec.signal_vec_cloned())
+                })
+                .collect(),
+        });
+    }
+
+    fn clear(&mut self) {
+        self.reset();
+        self.pending.push_back(VecDiff::Clear {});
+    }
+
+    fn reset(&mut self) {
+        self.items.clear();
+        self.groups.groups.clear();
+        self.groups.handles.clear();
+    }
+}
+
+impl<S, F, K> SignalVec for GroupBy<S, F, K>
+where
+    S: SignalVec,
+    S::Item: Clone,
+    F: FnMut(&S::Item) -> K,
+    K: PartialEq + Clone,
+{
+    type Item = (K, MutableSignalVec<S::Item>);
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(diff) = this.pending.pop_front() {
+                return Poll::Ready(Some(diff));
+            }
+
+            let diff = match this
+                .signal
+                .as_mut()
+                .as_pin_mut()
+                .map(|signal| signal.poll_vec_change(cx))
+            {
+                Some(Poll::Ready(Some(diff))) => diff,
+                Some(Poll::Ready(None)) => {
+                    this.signal.set(None);
+                    return Poll::Ready(None);
+                }
+                Some(Poll::Pending) => return Poll::Pending,
+                None => return Poll::Ready(None),
+            };
+
+            let mut grouping = Grouping {
+                key: this.key,
+                locate: *this.locate,
+                items: this.items,
+                groups: this.groups,
+                pending: this.pending,
+            };
+            match diff {
+                VecDiff::Replace { values } => grouping.replace(values),
+                VecDiff::InsertAt { index, value } => grouping.insert(index, value),
+                VecDiff::UpdateAt { index, value } => grouping.update(index, value),
+                VecDiff::RemoveAt { index } => grouping.remove(index),
+                VecDiff::Move {
+                    old_index,
+                    new_index,
+                } => grouping.move_item(old_index, new_index),
+                VecDiff::Push { value } => grouping.insert(grouping.items.len(), value),
+                VecDiff::Pop {} => grouping.remove(grouping.items.len() - 1),
+                VecDiff::Clear {} => grouping.clear(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::pin::{Pin, pin};
+
+    use futures_signals::signal_vec::{MutableVec, VecDiff};
+
+    use crate::{SignalVecGroupExt, testing::poll_vec};
+
+    #[test]
+    fn group_by() {
+        let vec = MutableVec::new_with_values(vec![1, 2, 3]);
+        let mut grouped = pin!(vec.signal_vec().group_by(|item| item % 2 == 0));
+        let Some(VecDiff::Replace { values }) = poll_vec(grouped.as_mut()).pop() else {
+            panic!("initial groups");
+        };
+        let mut groups = values.into_iter();
+        let (Some((false, mut odd)), Some((true, mut even))) = (groups.next(), groups.next())
+        else {
+            panic!("groups by first appearance");
+        };
+        assert_eq!(
+            poll_vec(Pin::new(&mut odd)),
+            vec![VecDiff::Replace { values: vec![1, 3] }]
+        );
+        assert_eq!(
+            poll_vec(Pin::new(&mut even)),
+            vec![VecDiff::Replace { values: vec![2] }]
+        );
+
+        vec.lock_mut().set(1, 5);
+        assert!(matches!(
+            poll_vec(grouped.as_mut()).as_slice(),
+            [VecDiff::RemoveAt { index: 1 }]
+        ));
+        assert_eq!(
+            poll_vec(Pin::new(&mut odd)),
+            vec![VecDiff::InsertAt { index: 1, value: 5 }]
+        );
+
+        vec.lock_mut().move_from_to(0, 2);
+        assert!(poll_vec(grouped.as_mut()).is_empty());
+        assert_eq!(
+            poll_vec(Pin::new(&mut odd)),
+            vec![VecDiff::Move {
+                old_index: 0,
+                new_index: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn group_by_ord() {
+        let vec = MutableVec::new_with_values(vec![3, 1, 2, 1]);
+        let mut grouped = pin!(vec.signal_vec().group_by_ord(|item| *item));
+        let Some(VecDiff::Replace { values }) = poll_vec(grouped.as_mut()).pop() else {
+            panic!("initial groups");
+        };
+        let keys = values.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+        assert_eq!(keys, vec![1, 2, 3]);
+
+        vec.lock_mut().push(0);
+        vec.lock_mut().retain(|item| *item != 2);
+        let diffs = poll_vec(grouped.as_mut());
+        assert!(matches!(
+            diffs.as_slice(),
+            [
+                VecDiff::InsertAt { index: 0, .. },
+                VecDiff::RemoveAt { index: 2 }
+            ]
+        ));
+    }
+
+    #[test]
+    fn group_by_appearance() {
+        let vec = MutableVec::new_with_values(vec![10, 20]);
+        let mut grouped = pin!(vec.signal_vec().group_by(|item| item / 10));
+        let mut groups = vec![];
+        let mut apply = |diffs: Vec<VecDiff<_>>| {
+            for diff in diffs {
+                diff.apply_to_vec(&mut groups);
+            }
+            groups.iter().map(|(key, _)| *key).collect::<Vec<i32>>()
+        };
+        assert_eq!(apply(poll_vec(grouped.as_mut())), vec![1, 2]);
+
+        vec.lock_mut().insert(0, 5);
+        assert_eq!(apply(poll_vec(grouped.as_mut())), vec![0, 1, 2]);
+
+        vec.lock_mut().remove(1);
+        vec.lock_mut().insert(0, 11);
+        assert_eq!(apply(poll_vec(grouped.as_mut())), vec![1, 0, 2]);
+
+        // the first item of a group moves, so does the group
+        vec.lock_mut().move_from_to(0, 2);
+        let diffs = poll_vec(grouped.as_mut());
+        assert!(matches!(
+            diffs.as_slice(),
+            [VecDiff::Move {
+                old_index: 0,
+                new_index: 2
+            }]
+        ));
+        assert_eq!(apply(diffs), vec![0, 2, 1]);
+
+        // the first item of a group is removed, the group follows the next one
+        vec.lock_mut().push(6);
+        vec.lock_mut().remove(0);
+        assert_eq!(apply(poll_vec(grouped.as_mut())), vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn group_by_matches_reference() {
+        let vec = MutableVec::new();
+        let mut grouped = pin!(vec.signal_vec().group_by(|item: &u32| item % 5));
+        let mut groups = vec![];
+        let mut seed = 11u32;
+        let mut random = |bound: usize| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 8) as usize % bound
+        };
+
+        for step in 0..500 {
+            {
+                let mut lock = vec.lock_mut();
+                let len = lock.len();
+                match random(5) {
+                    0 | 1 => lock.insert(random(len + 1), random(100) as u32),
+                    2 if len > 0 => {
+                        lock.remove(random(len));
+                    }
+                    3 if len > 0 => lock.move_from_to(random(len), random(len)),
+                    4 if len > 0 => lock.set(random(len), random(100) as u32),
+                    _ => {}
+                }
+            }
+            for diff in poll_vec(grouped.as_mut()) {
+                diff.apply_to_vec(&mut groups);
+            }
+
+            let items = vec.lock_ref().to_vec();
+            let mut expected = vec![];
+            for item in &items {
+                if !expected.contains(&(item % 5)) {
+                    expected.push(item % 5);
+                }
+            }
+            let keys = groups.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+            assert_eq!(keys, expected, "step {step}");
+        }
+
+        for (key, signal) in &mut groups {
+            let mut values = vec![];
+            for diff in poll_vec(Pin::new(signal)) {
+                diff.apply_to_vec(&mut values);
+            }
+            let expected = vec
+                .lock_ref()
+                .iter()
+                .copied()
+                .filter(|item| item % 5 == *key)
+                .collect::<Vec<_>>();
+            assert_eq!(values, expected);
+        }
+    }
+
+    #[test]
+    fn group_by_positions() {
+        let vec = MutableVec::new_with_values((0..12).collect());
+        let mut grouped = pin!(vec.signal_vec().group_by_ord(|item| item % 3));
+        let Some(VecDiff::Replace { values }) = poll_vec(grouped.as_mut()).pop() else {
+            panic!("initial groups");
+        };
+        let mut groups = values
+            .into_iter()
+            .map(|(key, signal)| (key, signal, vec![]))
+            .collect::<Vec<_>>();
+
+        {
+            let mut lock = vec.lock_mut();
+            lock.move_from_to(0, 7);
+            lock.move_from_to(10, 2);
+            lock.remove(4);
+            lock.insert(1, 12);
+            lock.set(5, 13);
+            lock.move_from_to(3, 3);
+            lock.push(14);
+        }
+        assert!(poll_vec(grouped.as_mut()).is_empty());
+        for (key, signal, items) in &mut groups {
+            for diff in poll_vec(Pin::new(signal)) {
+                diff.apply_to_vec(items);
+            }
+            let expected = vec
+                .lock_ref()
+                .iter()
+                .copied()
+                .filter(|item| item % 3 == *key)
+                .collect::<Vec<_>>();
+            assert_eq!(*items, expected);
+        }
+    }
+}
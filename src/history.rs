@@ -0,0 +1,359 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use futures_signals::{
+    signal::{Mutable, MutableLockRef, MutableSignal, MutableSignalCloned, Signal},
+    signal_vec::{MutableSignalVec, MutableVec, MutableVecLockRef, VecDiff},
+};
+
+use crate::{Transaction, transaction::apply_diff_inverse, version::bump_version};
+
+/// Target of `History`, knows how to revert a recorded change.
+pub trait Historic {
+    type Change;
+
+    /// Reverts the change and returns the change reverting the revert.
+    fn revert(&self, change: Self::Change) -> Self::Change;
+
+    /// Merges two subsequent changes into one undo step.
+    fn merge(older: Self::Change, newer: Self::Change) -> Self::Change;
+}
+
+impl<A: Clone> Historic for MutableVec<A> {
+    // inverse diffs, in the order they have to be applied
+    type Change = Vec<VecDiff<A>>;
+
+    fn revert(&self, change: Self::Change) -> Self::Change {
+        let mut lock = self.lock_mut();
+        let mut inverse = change
+            .into_iter()
+            .map(|diff| apply_diff_inverse(&mut lock, diff))
+            .collect::<Vec<_>>();
+        inverse.reverse();
+        bump_version();
+        inverse
+    }
+
+    fn merge(older: Self::Change, mut newer: Self::Change) -> Self::Change {
+        newer.extend(older);
+        newer
+    }
+}
+
+impl<A: Clone> Historic for Mutable<A> {
+    // previous value
+    type Change = A;
+
+    fn revert(&self, change: Self::Change) -> Self::Change {
+        let value = self.replace(change);
+        bump_version();
+        value
+    }
+
+    fn merge(older: Self::Change, _: Self::Change) -> Self::Change {
+        older
+    }
+}
+
+/// Undo/redo history of a `MutableVec` or a `Mutable`. Changes done within the time
+/// window (started by the first change) are grouped into a single undo step.
+/// At most `depth` steps are kept. The target is only readable through the history,
+/// so every change gets recorded.
+pub struct History<M: Historic> {
+    target: M,
+    undo: VecDeque<M::Change>,
+    redo: Vec<M::Change>,
+    depth: usize,
+    window: Duration,
+    group: Option<Instant>,
+    can_undo: Mutable<bool>,
+    can_redo: Mutable<bool>,
+}
+
+impl<M: Historic> History<M> {
+    /// Every change is a separate undo step.
+    pub fn new(target: M, depth: usize) -> Self {
+        Self::with_window(target, depth, Duration::ZERO)
+    }
+
+    pub fn with_window(target: M, depth: usize, window: Duration) -> Self {
+        Self {
+            target,
+            undo: VecDeque::new(),
+            redo: vec![],
+            depth,
+            window,
+            group: None,
+            can_undo: Mutable::new(false),
+            can_redo: Mutable::new(false),
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn signal_can_undo(&self) -> impl Signal<Item = bool> + use<M> {
+        self.can_undo.signal()
+    }
+
+    pub fn signal_can_redo(&self) -> impl Signal<Item = bool> + use<M> {
+        self.can_redo.signal()
+    }
+
+    pub fn undo(&mut self) -> bool {
+        self.group = None;
+        match self.undo.pop_back() {
+            Some(change) => {
+                self.redo.push(self.target.revert(change));
+                self.update_flags();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        self.group = None;
+        match self.redo.pop() {
+            Some(change) => {
+                self.undo.push_back(self.target.revert(change));
+                self.update_flags();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forgets all undo and redo steps.
+    pub fn clear_history(&mut self) {
+        self.group = None;
+        self.undo.clear();
+        self.redo.clear();
+        self.update_flags();
+    }
+
+    fn record(&mut self, change: M::Change) {
+        self.redo.clear();
+
+        // the group is open until its deadline
+        let now = Instant::now();
+        let grouped = self.group.is_some_and(|deadline| now < deadline);
+
+        match self.undo.pop_back() {
+            Some(older) if grouped => self.undo.push_back(M::merge(older, change)),
+            older => {
+                self.undo.extend(older);
+                self.undo.push_back(change);
+                while self.undo.len() > self.depth {
+                    self.undo.pop_front();
+                }
+
+                self.group = (!self.window.is_zero()).then(|| now + self.window);
+            }
+        }
+
+        self.update_flags();
+    }
+
+    fn update_flags(&self) {
+        self.can_undo.set_neq(!self.undo.is_empty());
+        self.can_redo.set_neq(!self.redo.is_empty());
+    }
+}
+
+impl<A: Clone> History<MutableVec<A>> {
+    /// All changes done in F are recorded as a single undo step.
+    pub fn transaction<U>(&mut self, f: impl FnOnce(&mut Transaction<A>) -> U) -> U {
+        let mut inverse = vec![];
+        let result = {
+            let mut lock = self.target.lock_mut();
+            let mut transaction = Transaction::new(lock.to_vec());
+            let result = f(&mut transaction);
+            transaction.commit(&mut lock, Some(&mut inverse));
+            result
+        };
+
+        if !inverse.is_empty() {
+            inverse.reverse();
+            self.record(inverse);
+        }
+
+        result
+    }
+
+    pub fn lock_ref(&self) -> MutableVecLockRef<'_, A> {
+        self.target.lock_ref()
+    }
+
+    pub fn signal_vec(&self) -> MutableSignalVec<A>
+    where
+        A: Copy,
+    {
+        self.target.signal_vec()
+    }
+
+    pub fn signal_vec_cloned(&self) -> MutableSignalVec<A> {
+        self.target.signal_vec_cloned()
+    }
+
+    pub fn push(&mut self, value: A) {
+        self.apply(VecDiff::Push { value });
+    }
+
+    pub fn insert(&mut self, index: usize, value: A) {
+        self.apply(VecDiff::InsertAt { index, value });
+    }
+
+    pub fn set(&mut self, index: usize, value: A) {
+        self.apply(VecDiff::UpdateAt { index, value });
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        self.apply(VecDiff::RemoveAt { index });
+    }
+
+    pub fn pop(&mut self) {
+        if !self.target.lock_ref().is_empty() {
+            self.apply(VecDiff::Pop {});
+        }
+    }
+
+    pub fn move_from_to(&mut self, old_index: usize, new_index: usize) {
+        if old_index != new_index {
+            self.apply(VecDiff::Move {
+                old_index,
+                new_index,
+            });
+        }
+    }
+
+    pub fn clear(&mut self) {
+        if !self.target.lock_ref().is_empty() {
+            self.apply(VecDiff::Clear {});
+        }
+    }
+
+    pub fn replace(&mut self, values: Vec<A>) {
+        self.apply(VecDiff::Replace { values });
+    }
+
+    fn apply(&mut self, diff: VecDiff<A>) {
+        let inverse = apply_diff_inverse(&mut self.target.lock_mut(), diff);
+        bump_version();
+        self.record(vec![inverse]);
+    }
+}
+
+impl<A: Clone> History<Mutable<A>> {
+    pub fn lock_ref(&self) -> MutableLockRef<'_, A> {
+        self.target.lock_ref()
+    }
+
+    pub fn get(&self) -> A
+    where
+        A: Copy,
+    {
+        self.target.get()
+    }
+
+    pub fn get_cloned(&self) -> A {
+        self.target.get_cloned()
+    }
+
+    pub fn signal(&self) -> MutableSignal<A>
+    where
+        A: Copy,
+    {
+        self.target.signal()
+    }
+
+    pub fn signal_cloned(&self) -> MutableSignalCloned<A> {
+        self.target.signal_cloned()
+    }
+
+    pub fn set(&mut self, value: A) {
+        let old = self.target.replace(value);
+        bump_version();
+        self.record(old);
+    }
+
+    pub fn set_neq(&mut self, value: A)
+    where
+        A: PartialEq,
+    {
+        if *self.target.lock_ref() != value {
+            self.set(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{thread, time::Duration};
+
+    use futures_signals::{signal::Mutable, signal_vec::MutableVec};
+
+    use crate::History;
+
+    #[test]
+    fn undo_redo_vec() {
+        let mut history = History::new(MutableVec::new_with_values(vec![1, 2, 3]), 10);
+        history.push(4);
+        history.transaction(|tx| {
+            tx.remove(0);
+            tx.set(0, 20);
+        });
+        assert_eq!(history.lock_ref().as_slice(), &[20, 3, 4]);
+
+        assert!(history.undo());
+        assert_eq!(history.lock_ref().as_slice(), &[1, 2, 3, 4]);
+        assert!(history.undo());
+        assert_eq!(history.lock_ref().as_slice(), &[1, 2, 3]);
+        assert!(!history.can_undo());
+
+        assert!(history.redo());
+        assert!(history.redo());
+        assert_eq!(history.lock_ref().as_slice(), &[20, 3, 4]);
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn grouped_and_bounded() {
+        let mut history = History::with_window(Mutable::new(0), 1, Duration::from_secs(3600));
+        history.set(1);
+        history.set(2);
+        history.set(3);
+        assert!(history.undo());
+        assert_eq!(history.get(), 0);
+        assert!(!history.undo());
+
+        let mut history = History::new(Mutable::new(0), 2);
+        history.set(1);
+        history.set(2);
+        history.set(3);
+        assert!(history.undo());
+        assert!(history.undo());
+        assert_eq!(history.get(), 1);
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn window_closes() {
+        let mut history = History::with_window(Mutable::new(0), 10, Duration::from_millis(50));
+        history.set(1);
+        history.set(2);
+        thread::sleep(Duration::from_millis(100));
+        history.set(3);
+        assert!(history.undo());
+        assert_eq!(history.get(), 2);
+        assert!(history.undo());
+        assert_eq!(history.get(), 0);
+    }
+}
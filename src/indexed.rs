@@ -0,0 +1,252 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_signals::{
+    signal::{Mutable, ReadOnlyMutable},
+    signal_vec::{SignalVec, VecDiff},
+};
+use pin_project_lite::pin_project;
+
+use crate::Flatten;
+
+/// Item with its current outer and inner index.
+#[derive(Clone, Debug)]
+pub struct Indexed<T> {
+    pub item: T,
+    pub outer: ReadOnlyMutable<usize>,
+    pub inner: ReadOnlyMutable<usize>,
+}
+
+pub type FlattenIndexed<A> = Flatten<IndexedOuter<A>>;
+
+// Index of each position, updated as positions shift.
+#[derive(Debug, Default)]
+struct Indexes(Vec<Mutable<usize>>);
+
+impl Indexes {
+    fn renumber(&self, from: usize, to: usize) {
+        for (index, mutable) in self.0[from..to].iter().enumerate() {
+            mutable.set_neq(from + index);
+        }
+    }
+
+    fn replace(&mut self, len: usize) -> impl Iterator<Item = ReadOnlyMutable<usize>> + '_ {
+        self.0 = (0..len).map(Mutable::new).collect();
+        self.0.iter().map(Mutable::read_only)
+    }
+
+    fn insert(&mut self, index: usize) -> ReadOnlyMutable<usize> {
+        self.0.insert(index, Mutable::new(index));
+        self.renumber(index + 1, self.0.len());
+        self.0[index].read_only()
+    }
+
+    fn get(&self, index: usize) -> ReadOnlyMutable<usize> {
+        self.0[index].read_only()
+    }
+
+    fn remove(&mut self, index: usize) {
+        self.0.remove(index);
+        self.renumber(index, self.0.len());
+    }
+
+    fn move_item(&mut self, old_index: usize, new_index: usize) {
+        let mutable = self.0.remove(old_index);
+        self.0.insert(new_index, mutable);
+        self.renumber(old_index.min(new_index), old_index.max(new_index) + 1);
+    }
+
+    // diff with items paired with the index of their position
+    fn apply<T, U>(
+        &mut self,
+        diff: VecDiff<T>,
+        mut f: impl FnMut(T, ReadOnlyMutable<usize>) -> U,
+    ) -> VecDiff<U> {
+        match diff {
+            VecDiff::Replace { values } => {
+                let len = values.len();
+                VecDiff::Replace {
+                    values: values
+                        .into_iter()
+                        .zip(self.replace(len))
+                        .map(|(item, index)| f(item, index))
+                        .collect(),
+                }
+            }
+            VecDiff::InsertAt { index, value } => VecDiff::InsertAt {
+                index,
+                value: f(value, self.insert(index)),
+            },
+            VecDiff::UpdateAt { index, value } => VecDiff::UpdateAt {
+                index,
+                value: f(value, self.get(index)),
+            },
+            VecDiff::RemoveAt { index } => {
+                self.remove(index);
+                VecDiff::RemoveAt { index }
+            }
+            VecDiff::Move {
+                old_index,
+                new_index,
+            } => {
+                self.move_item(old_index, new_index);
+                VecDiff::Move {
+                    old_index,
+                    new_index,
+                }
+            }
+            VecDiff::Push { value } => VecDiff::Push {
+                value: f(value, self.insert(self.0.len())),
+            },
+            VecDiff::Pop {} => {
+                self.0.pop();
+                VecDiff::Pop {}
+            }
+            VecDiff::Clear {} => {
+                self.0.clear();
+                VecDiff::Clear {}
+            }
+        }
+    }
+}
+
+pin_project! {
+    /// Items of one inner signal vec with their indexes.
+    #[derive(Debug)]
+    #[must_use = "SignalVecs do nothing unless polled"]
+    pub struct IndexedInner<S> {
+        #[pin]
+        signal: S,
+        outer: ReadOnlyMutable<usize>,
+        indexes: Indexes,
+    }
+}
+
+impl<S: SignalVec> SignalVec for IndexedInner<S> {
+    type Item = Indexed<S::Item>;
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let this = self.project();
+        let outer = &*this.outer;
+        let indexed = |item, inner| Indexed {
+            item,
+            outer: outer.clone(),
+            inner,
+        };
+        let indexes = this.indexes;
+
+        this.signal
+            .poll_vec_change(cx)
+            .map(|diff| diff.map(|diff| indexes.apply(diff, indexed)))
+    }
+}
+
+pin_project! {
+    /// Inner signal vecs with their outer index, see
+    /// `SignalVecFlattenExt::flatten_indexed`.
+    #[derive(Debug)]
+    #[must_use = "SignalVecs do nothing unless polled"]
+    pub struct IndexedOuter<A> {
+        #[pin]
+        signal: A,
+        indexes: Indexes,
+    }
+}
+
+impl<A> IndexedOuter<A> {
+    pub(crate) fn new(signal: A) -> Self {
+        Self {
+            signal,
+            indexes: Indexes::default(),
+        }
+    }
+}
+
+impl<A> SignalVec for IndexedOuter<A>
+where
+    A: SignalVec,
+    A::Item: SignalVec,
+{
+    type Item = IndexedInner<A::Item>;
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let this = self.project();
+        let indexed = |signal, outer| IndexedInner {
+            signal,
+            outer,
+            indexes: Indexes::default(),
+        };
+        let indexes = this.indexes;
+
+        this.signal
+            .poll_vec_change(cx)
+            .map(|diff| diff.map(|diff| indexes.apply(diff, indexed)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::pin::pin;
+
+    use futures_signals::signal_vec::{MutableVec, SignalVecExt};
+
+    use crate::{Indexed, SignalVecFlattenExt, testing::poll_vec};
+
+    fn paths(items: &[Indexed<char>]) -> Vec<(char, usize, usize)> {
+        items
+            .iter()
+            .map(|indexed| (indexed.item, indexed.outer.get(), indexed.inner.get()))
+            .collect()
+    }
+
+    #[test]
+    fn flatten_indexed() {
+        let a = MutableVec::new_with_values(vec!['a', 'b']);
+        let b = MutableVec::new_with_values(vec!['c']);
+        let outer = MutableVec::new_with_values(vec![a.clone(), b.clone()]);
+        let mut flatten = pin!(
+            outer
+                .signal_vec_cloned()
+                .map(|inner| inner.signal_vec())
+                .flatten_indexed()
+        );
+        let mut items = vec![];
+        for diff in poll_vec(flatten.as_mut()) {
+            diff.apply_to_vec(&mut items);
+        }
+        assert_eq!(paths(&items), vec![('a', 0, 0), ('b', 0, 1), ('c', 1, 0)]);
+
+        outer
+            .lock_mut()
+            .insert_cloned(0, MutableVec::new_with_values(vec!['d']));
+        a.lock_mut().move_from_to(1, 0);
+        b.lock_mut().insert(0, 'e');
+        for diff in poll_vec(flatten.as_mut()) {
+            diff.apply_to_vec(&mut items);
+        }
+        assert_eq!(
+            paths(&items),
+            vec![
+                ('d', 0, 0),
+                ('b', 1, 0),
+                ('a', 1, 1),
+                ('e', 2, 0),
+                ('c', 2, 1)
+            ]
+        );
+
+        // the kept item is addressed by its updated path
+        let c = items.pop().unwrap();
+        outer.lock_mut().remove(0);
+        poll_vec(flatten.as_mut());
+        assert_eq!((c.outer.get(), c.inner.get()), (1, 1));
+    }
+}
@@ -0,0 +1,598 @@
+use std::{
+    collections::{BTreeSet, VecDeque},
+    hash::Hash,
+    iter, mem,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_signals::signal_vec::{SignalVec, VecDiff};
+use pin_project_lite::pin_project;
+
+use crate::{
+    ext::{HashMap, collect_hash_map},
+    filter::Visibility,
+    sequence::Sequence,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JoinKind {
+    /// Left items without a matching right item are left out.
+    Inner,
+    /// All left items, with `None` if there is no matching right item.
+    Left,
+}
+
+type Pending<A, B> = VecDeque<VecDiff<(A, Option<B>)>>;
+
+// Items of both sides. Of the right items with equal keys the first one matches.
+#[derive(Debug)]
+struct Joined<K, A, B> {
+    kind: JoinKind,
+    left: Sequence<(K, A)>,
+    // handles of the left items with the key
+    left_by_key: HashMap<K, BTreeSet<usize>>,
+    visibility: Visibility,
+    // right items with the handle of their entry in right_by_key
+    right: Sequence<(K, B, usize)>,
+    // handles of the right items with the key in the source order, the first matches
+    right_by_key: HashMap<K, Sequence<usize>>,
+}
+
+impl<K, A, B> Joined<K, A, B>
+where
+    K: Eq + Hash + Clone,
+    A: Clone,
+    B: Clone,
+{
+    fn value(&self, index: usize) -> (bool, (A, Option<B>)) {
+        let (key, item) = self.left.get(index);
+        let matched = self
+            .right_by_key
+            .get(key)
+            .map(|handles| self.right.value(*handles.get(0)).1.clone());
+        (
+            self.kind == JoinKind::Left || matched.is_some(),
+            (item.clone(), matched),
+        )
+    }
+
+    fn link(&mut self, key: K, handle: usize) {
+        self.left_by_key.entry(key).or_default().insert(handle);
+    }
+
+    fn unlink(&mut self, key: &K, handle: usize) {
+        let handles = self
+            .left_by_key
+            .get_mut(key)
+            .expect("left items with the key");
+        handles.remove(&handle);
+        if handles.is_empty() {
+            self.left_by_key.remove(key);
+        }
+    }
+
+    fn apply_left<F>(&mut self, diff: VecDiff<A>, mut key: F, pending: &mut Pending<A, B>)
+    where
+        F: FnMut(&A) -> K,
+    {
+        match diff {
+            VecDiff::Replace { values } => {
+                self.left.clear();
+                self.left_by_key.clear();
+                for value in values {
+                    let key = key(&value);
+                    let handle = self.left.push((key.clone(), value), 0);
+                    self.link(key, handle);
+                }
+                let values = (0..self.left.len())
+                    .map(|index| self.value(index))
+                    .collect::<Vec<_>>();
+                self.visibility.replace(values, pending);
+            }
+            VecDiff::InsertAt { index, value } => {
+                self.insert_left(index, key(&value), value, pending)
+            }
+            VecDiff::UpdateAt { index, value } => {
+                let key = key(&value);
+                let handle = self.left.handle(index);
+                let (old_key, _) = mem::replace(self.left.get_mut(index), (key.clone(), value));
+                if old_key != key {
+                    self.unlink(&old_key, handle);
+                    self.link(key, handle);
+                }
+                let (visible, value) = self.value(index);
+                self.visibility.update(index, visible, value, pending);
+            }
+            VecDiff::RemoveAt { index } => self.remove_left(index, pending),
+            VecDiff::Move {
+                old_index,
+                new_index,
+            } => {
+                self.left.move_item(old_index, new_index);
+                self.visibility.move_item(old_index, new_index, pending);
+            }
+            VecDiff::Push { value } => {
+                self.insert_left(self.left.len(), key(&value), value, pending)
+            }
+            VecDiff::Pop {} => self.remove_left(self.left.len() - 1, pending),
+            VecDiff::Clear {} => {
+                self.left.clear();
+                self.left_by_key.clear();
+                self.visibility.clear(pending);
+            }
+        }
+    }
+
+    fn insert_left(&mut self, index: usize, key: K, item: A, pending: &mut Pending<A, B>) {
+        let handle = self.left.insert(index, (key.clone(), item), 0);
+        self.link(key, handle);
+        let (visible, value) = self.value(index);
+        self.visibility.insert(index, visible, value, pending);
+    }
+
+    fn remove_left(&mut self, index: usize, pending: &mut Pending<A, B>) {
+        let handle = self.left.handle(index);
+        let (key, _) = self.left.remove(index);
+        self.unlink(&key, handle);
+        self.visibility.remove(index, pending);
+    }
+
+    fn apply_right<F>(&mut self, diff: VecDiff<B>, mut key: F, pending: &mut Pending<A, B>)
+    where
+        F: FnMut(&B) -> K,
+    {
+        match diff {
+            VecDiff::Replace { values } => {
+                let mut keys = self.clear_right();
+                for value in values {
+                    let key = key(&value);
+                    self.insert_right(self.right.len(), key.clone(), value);
+                    keys.push(key);
+                }
+                self.rematch_keys(keys, pending);
+            }
+            VecDiff::InsertAt { index, value } => {
+                let key = key(&value);
+                if self.insert_right(index, key.clone(), value) {
+                    self.rematch(&key, pending);
+                }
+            }
+            VecDiff::UpdateAt { index, value } => {
+                let key = key(&value);
+                if key == self.right.get(index).0 {
+                    let (_, item, handle) = self.right.get_mut(index);
+                    *item = value;
+                    let handle = *handle;
+                    if self.right_by_key[&key].index_of(handle) == 0 {
+                        self.rematch(&key, pending);
+                    }
+                } else {
+                    let old_key = self.right.get(index).0.clone();
+                    if self.remove_right(index) {
+                        self.rematch(&old_key, pending);
+                    }
+                    if self.insert_right(index, key.clone(), value) {
+                        self.rematch(&key, pending);
+                    }
+                }
+            }
+            VecDiff::RemoveAt { index } => {
+                let key = self.right.get(index).0.clone();
+                if self.remove_right(index) {
+                    self.rematch(&key, pending);
+                }
+            }
+            VecDiff::Move {
+                old_index,
+                new_index,
+            } => {
+                self.right.move_item(old_index, new_index);
+                let item = self.right.handle(new_index);
+                let (key, _, handle) = self.right.value(item);
+                let right = &self.right;
+                let handles = self
+                    .right_by_key
+                    .get_mut(key)
+                    .expect("right items with the key");
+                let old_position = handles.index_of(*handle);
+                handles.remove(old_position);
+                let new_position =
+                    handles.partition_point(|handle| right.index_of(*handle) < new_index);
+                let handle = handles.insert(new_position, item, 0);
+                let key = key.clone();
+                self.right.value_mut(item).2 = handle;
+                // the first item with the key changed
+                if (old_position == 0) != (new_position == 0) {
+                    self.rematch(&key, pending);
+                }
+            }
+            VecDiff::Push { value } => {
+                let key = key(&value);
+                if self.insert_right(self.right.len(), key.clone(), value) {
+                    self.rematch(&key, pending);
+                }
+            }
+            VecDiff::Pop {} => {
+                let index = self.right.len() - 1;
+                let key = self.right.get(index).0.clone();
+                if self.remove_right(index) {
+                    self.rematch(&key, pending);
+                }
+            }
+            VecDiff::Clear {} => {
+                let keys = self.clear_right();
+                self.rematch_keys(keys, pending);
+            }
+        }
+    }
+
+    // true if the item is the first one with the key
+    fn insert_right(&mut self, index: usize, key: K, item: B) -> bool {
+        let item = self.right.insert(index, (key.clone(), item, 0), 0);
+        let right = &self.right;
+        let handles = self.right_by_key.entry(key).or_default();
+        let position = handles.partition_point(|handle| right.index_of(*handle) < index);
+        self.right.value_mut(item).2 = handles.insert(position, item, 0);
+        position == 0
+    }
+
+    // true if the item was the first one with the key
+    fn remove_right(&mut self, index: usize) -> bool {
+        let (key, _, handle) = self.right.remove(index);
+        let handles = self
+            .right_by_key
+            .get_mut(&key)
+            .expect("right items with the key");
+        let position = handles.index_of(handle);
+        handles.remove(position);
+        if handles.len() == 0 {
+            self.right_by_key.remove(&key);
+        }
+        position == 0
+    }
+
+    // returns keys of the removed items
+    fn clear_right(&mut self) -> Vec<K> {
+        self.right.clear();
+        self.right_by_key.drain().map(|(key, _)| key).collect()
+    }
+
+    // left items with the key are emitted with the new match
+    fn rematch(&mut self, key: &K, pending: &mut Pending<A, B>) {
+        let Some(handles) = self.left_by_key.get(key) else {
+            return;
+        };
+        let mut indexes = handles
+            .iter()
+            .map(|handle| self.left.index_of(*handle))
+            .collect::<Vec<_>>();
+        indexes.sort_unstable();
+        for index in indexes {
+            let (visible, value) = self.value(index);
+            self.visibility.update(index, visible, value, pending);
+        }
+    }
+
+    // only left items with keys of the right items before or after the change
+    fn rematch_keys(&mut self, keys: Vec<K>, pending: &mut Pending<A, B>) {
+        let mut indexes = keys
+            .into_iter()
+            .filter_map(|key| self.left_by_key.get(&key))
+            .flatten()
+            .map(|handle| self.left.index_of(*handle))
+            .collect::<Vec<_>>();
+        indexes.sort_unstable();
+        indexes.dedup();
+        for index in indexes {
+            let (visible, value) = self.value(index);
+            self.visibility.update(index, visible, value, pending);
+        }
+    }
+}
+
+pin_project! {
+    /// Left items joined with the first right item with equal key.
+    #[derive(Debug)]
+    #[must_use = "SignalVecs do nothing unless polled"]
+    pub struct JoinByKey<L, R, FL, FR, K>
+    where
+        L: SignalVec,
+        R: SignalVec,
+    {
+        #[pin]
+        left: Option<L>,
+        #[pin]
+        right: Option<R>,
+        left_key: FL,
+        right_key: FR,
+        joined: Joined<K, L::Item, R::Item>,
+        pending: Pending<L::Item, R::Item>,
+    }
+}
+
+impl<L, R, FL, FR, K> JoinByKey<L, R, FL, FR, K>
+where
+    L: SignalVec,
+    R: SignalVec,
+    K: Eq + Hash,
+{
+    pub(crate) fn new(left: L, right: R, left_key: FL, right_key: FR, kind: JoinKind) -> Self {
+        Self {
+            left: Some(left),
+            right: Some(right),
+            left_key,
+            right_key,
+            joined: Joined {
+                kind,
+                left: Sequence::default(),
+                left_by_key: collect_hash_map(iter::empty()),
+                visibility: Visibility::default(),
+                right: Sequence::default(),
+                right_by_key: collect_hash_map(iter::empty()),
+            },
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl<L, R, FL, FR, K> SignalVec for JoinByKey<L, R, FL, FR, K>
+where
+    L: SignalVec,
+    L::Item: Clone,
+    R: SignalVec,
+    R::Item: Clone,
+    FL: FnMut(&L::Item) -> K,
+    FR: FnMut(&R::Item) -> K,
+    K: Eq + Hash + Clone,
+{
+    type Item = (L::Item, Option<R::Item>);
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(diff) = this.pending.pop_front() {
+                return Poll::Ready(Some(diff));
+            }
+
+            let right = match this
+                .right
+                .as_mut()
+                .as_pin_mut()
+                .map(|signal| signal.poll_vec_change(cx))
+            {
+                Some(Poll::Ready(Some(diff))) => {
+                    this.joined
+                        .apply_right(diff, &mut *this.right_key, this.pending);
+                    continue;
+                }
+                Some(Poll::Ready(None)) => {
+                    this.right.set(None);
+                    true
+                }
+                Some(Poll::Pending) => false,
+                None => true,
+            };
+
+            match this
+                .left
+                .as_mut()
+                .as_pin_mut()
+                .map(|signal| signal.poll_vec_change(cx))
+            {
+                Some(Poll::Ready(Some(diff))) => {
+                    this.joined
+                        .apply_left(diff, &mut *this.left_key, this.pending);
+                }
+                Some(Poll::Ready(None)) => this.left.set(None),
+                Some(Poll::Pending) => return Poll::Pending,
+                None if right => return Poll::Ready(None),
+                None => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::pin::pin;
+
+    use futures_signals::signal_vec::{MutableVec, SignalVecExt, VecDiff};
+
+    use crate::{
+        JoinKind, SignalVecKeyedExt,
+        testing::{poll_signal, poll_vec},
+    };
+
+    #[test]
+    fn join_by_key() {
+        let users = MutableVec::new_with_values(vec![(1, "ann"), (2, "bob"), (3, "cid")]);
+        let presence = MutableVec::new_with_values(vec![(2, true), (3, false)]);
+        let join = |kind| {
+            users
+                .signal_vec()
+                .join_by_key(
+                    presence.signal_vec(),
+                    |user| user.0,
+                    |status| status.0,
+                    kind,
+                )
+                .map(|(user, status)| (user.1, status.map(|status| status.1)))
+                .to_signal_cloned()
+        };
+        let mut inner = pin!(join(JoinKind::Inner));
+        let mut left = pin!(join(JoinKind::Left));
+        assert_eq!(
+            poll_signal(inner.as_mut()).pop(),
+            Some(vec![("bob", Some(true)), ("cid", Some(false))])
+        );
+        assert_eq!(
+            poll_signal(left.as_mut()).pop(),
+            Some(vec![
+                ("ann", None),
+                ("bob", Some(true)),
+                ("cid", Some(false))
+            ])
+        );
+
+        presence.lock_mut().insert(0, (1, true));
+        presence.lock_mut().push((2, false));
+        presence.lock_mut().remove(1);
+        users.lock_mut().push((4, "dan"));
+        assert_eq!(
+            poll_signal(inner.as_mut()).pop(),
+            Some(vec![
+                ("ann", Some(true)),
+                ("bob", Some(false)),
+                ("cid", Some(false))
+            ])
+        );
+        assert_eq!(
+            poll_signal(left.as_mut()).pop(),
+            Some(vec![
+                ("ann", Some(true)),
+                ("bob", Some(false)),
+                ("cid", Some(false)),
+                ("dan", None)
+            ])
+        );
+
+        presence.lock_mut().move_from_to(2, 0);
+        presence.lock_mut().set(0, (3, true));
+        presence.lock_mut().retain(|status| status.0 != 1);
+        assert_eq!(
+            poll_signal(inner.as_mut()).pop(),
+            Some(vec![("cid", Some(true))])
+        );
+    }
+
+    #[test]
+    fn left_keys_changed() {
+        let users = MutableVec::new_with_values(vec![(1, "ann"), (2, "bob"), (1, "cid")]);
+        let presence = MutableVec::new_with_values(vec![(1, true)]);
+        let mut join = pin!(
+            users
+                .signal_vec()
+                .join_by_key(
+                    presence.signal_vec(),
+                    |user| user.0,
+                    |status| status.0,
+                    JoinKind::Left,
+                )
+                .map(|(user, status)| (user.1, status.map(|status| status.1)))
+                .to_signal_cloned()
+        );
+        poll_signal(join.as_mut());
+
+        users.lock_mut().set(0, (2, "ann"));
+        users.lock_mut().move_from_to(2, 0);
+        users.lock_mut().remove(2);
+        presence.lock_mut().set(0, (1, false));
+        presence.lock_mut().push((2, true));
+        assert_eq!(
+            poll_signal(join.as_mut()).pop(),
+            Some(vec![("cid", Some(false)), ("ann", Some(true))])
+        );
+    }
+
+    #[test]
+    fn right_replaced() {
+        let users = MutableVec::new_with_values(vec![(1, "ann"), (2, "bob"), (3, "cid")]);
+        let presence = MutableVec::new_with_values(vec![(1, true), (3, true)]);
+        let mut join = pin!(
+            users
+                .signal_vec()
+                .join_by_key(
+                    presence.signal_vec(),
+                    |user| user.0,
+                    |status| status.0,
+                    JoinKind::Left,
+                )
+                .map(|(user, status)| (user.1, status.map(|status| status.1)))
+        );
+        poll_vec(join.as_mut());
+
+        // only rows whose keys are on the right before or after are updated
+        presence.lock_mut().replace(vec![(2, false), (1, false)]);
+        let mut updated = poll_vec(join.as_mut())
+            .into_iter()
+            .map(|diff| match diff {
+                VecDiff::UpdateAt { index, value } => (index, value),
+                diff => panic!("unexpected {diff:?}"),
+            })
+            .collect::<Vec<_>>();
+        updated.sort();
+        assert_eq!(
+            updated,
+            vec![
+                (0, ("ann", Some(false))),
+                (1, ("bob", Some(false))),
+                (2, ("cid", None))
+            ]
+        );
+
+        presence.lock_mut().clear();
+        let updated = poll_vec(join.as_mut());
+        assert_eq!(updated.len(), 2);
+    }
+
+    #[test]
+    fn join_matches_reference() {
+        let users = MutableVec::new();
+        let presence = MutableVec::new();
+        let mut join = pin!(
+            users
+                .signal_vec()
+                .join_by_key(
+                    presence.signal_vec(),
+                    |user: &(u32, u32)| user.0,
+                    |status: &(u32, u32)| status.0,
+                    JoinKind::Inner,
+                )
+                .to_signal_cloned()
+        );
+        let mut joined = vec![];
+        let mut seed = 5u32;
+        let mut random = |bound: usize| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 8) as usize % bound
+        };
+
+        for step in 0..400 {
+            let side = if random(2) == 0 { &users } else { &presence };
+            {
+                let mut lock = side.lock_mut();
+                let len = lock.len();
+                let item = (random(6) as u32, step);
+                match random(6) {
+                    0 | 1 => lock.insert(random(len + 1), item),
+                    2 if len > 0 => {
+                        lock.remove(random(len));
+                    }
+                    3 if len > 0 => lock.move_from_to(random(len), random(len)),
+                    4 if len > 0 => lock.set(random(len), item),
+                    5 if random(10) == 0 => lock.replace(vec![item]),
+                    _ => {}
+                }
+            }
+
+            let right = presence.lock_ref().to_vec();
+            let expected = users
+                .lock_ref()
+                .iter()
+                .filter_map(|user| {
+                    let status = right.iter().find(|status| status.0 == user.0)?;
+                    Some((*user, Some(*status)))
+                })
+                .collect::<Vec<_>>();
+            if let Some(value) = poll_signal(join.as_mut()).pop() {
+                joined = value;
+            }
+            assert_eq!(joined, expected, "step {step}");
+        }
+    }
+}
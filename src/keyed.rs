@@ -0,0 +1,370 @@
+use std::{
+    collections::VecDeque,
+    hash::Hash,
+    iter,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_signals::{
+    signal_map::{MapDiff, SignalMap},
+    signal_vec::{SignalVec, VecDiff},
+};
+use pin_project_lite::pin_project;
+
+use crate::{
+    ext::{HashMap, collect_hash_map},
+    sequence::Sequence,
+};
+
+/// Which of the items with equal keys is the value of the key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DuplicateKeys {
+    /// The item at the lowest index.
+    First,
+    /// The item at the highest index.
+    Last,
+}
+
+pin_project! {
+    #[derive(Debug)]
+    #[must_use = "SignalMaps do nothing unless polled"]
+    pub struct SignalMapByKey<S, F, K>
+    where
+        S: SignalVec,
+    {
+        #[pin]
+        signal: Option<S>,
+        key: F,
+        duplicates: DuplicateKeys,
+        // items with their key and the handle among the items with the key
+        items: Sequence<(K, S::Item, usize)>,
+        // handles of the items with the key, in the source order
+        members: HashMap<K, Sequence<usize>>,
+        pending: VecDeque<MapDiff<K, S::Item>>,
+    }
+}
+
+impl<S, F, K> SignalMapByKey<S, F, K>
+where
+    S: SignalVec,
+    K: Eq + Hash,
+{
+    pub(crate) fn new(signal: S, key: F, duplicates: DuplicateKeys) -> Self {
+        Self {
+            signal: Some(signal),
+            key,
+            duplicates,
+            items: Sequence::default(),
+            members: collect_hash_map(iter::empty()),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+struct Keyed<'a, K, T, F> {
+    key: &'a mut F,
+    duplicates: DuplicateKeys,
+    items: &'a mut Sequence<(K, T, usize)>,
+    members: &'a mut HashMap<K, Sequence<usize>>,
+    pending: &'a mut VecDeque<MapDiff<K, T>>,
+}
+
+impl<K, T, F> Keyed<'_, K, T, F>
+where
+    K: Eq + Hash + Clone,
+    T: Clone,
+    F: FnMut(&T) -> K,
+{
+    // handle of the item being the value of the key
+    fn winner(&self, key: &K) -> usize {
+        let members = &self.members[key];
+        match self.duplicates {
+            DuplicateKeys::First => *members.get(0),
+            DuplicateKeys::Last => *members.get(members.len() - 1),
+        }
+    }
+
+    fn update_winner(&mut self, key: K) {
+        let value = self.items.value(self.winner(&key)).1.clone();
+        self.pending.push_back(MapDiff::Update { key, value });
+    }
+
+    // position among the items with the key for an item placed at the index
+    fn position_at(&self, key: &K, index: usize) -> usize {
+        let items = &*self.items;
+        self.members.get(key).map_or(0, |members| {
+            members.partition_point(|handle| items.index_of(*handle) < index)
+        })
+    }
+
+    fn insert(&mut self, index: usize, value: T) {
+        let key = (self.key)(&value);
+        let position = self.position_at(&key, index);
+        let handle = self.items.insert(index, (key.clone(), value, 0), 0);
+        let members = self.members.entry(key.clone()).or_default();
+        self.items.get_mut(index).2 = members.insert(position, handle, 0);
+
+        if members.len() == 1 {
+            let value = self.items.get(index).1.clone();
+            self.pending.push_back(MapDiff::Insert { key, value });
+        } else if self.winner(&key) == handle {
+            self.update_winner(key);
+        }
+    }
+
+    fn update(&mut self, index: usize, value: T) {
+        let key = (self.key)(&value);
+        if key == self.items.get(index).0 {
+            self.items.get_mut(index).1 = value;
+            if self.winner(&key) == self.items.handle(index) {
+                self.update_winner(key);
+            }
+        } else {
+            self.remove(index);
+            self.insert(index, value);
+        }
+    }
+
+    fn remove(&mut self, index: usize) {
+        let winner = self.winner(&self.items.get(index).0) == self.items.handle(index);
+        let (key, _, member) = self.items.remove(index);
+
+        let members = self.members.get_mut(&key).expect("items with the key");
+        members.remove(members.index_of(member));
+        if members.len() == 0 {
+            self.members.remove(&key);
+            self.pending.push_back(MapDiff::Remove { key });
+        } else if winner {
+            self.update_winner(key);
+        }
+    }
+
+    fn move_item(&mut self, old_index: usize, new_index: usize) {
+        let (key, _, member) = self.items.get(old_index);
+        let (key, member) = (key.clone(), *member);
+        let handle = self.items.handle(old_index);
+        let winner = self.winner(&key) == handle;
+        self.items.move_item(old_index, new_index);
+
+        let members = self.members.get_mut(&key).expect("items with the key");
+        members.remove(members.index_of(member));
+        let position = self.position_at(&key, new_index);
+        let members = self.members.get_mut(&key).expect("items with the key");
+        self.items.get_mut(new_index).2 = members.insert(position, handle, 0);
+
+        if winner != (self.winner(&key) == handle) {
+            self.update_winner(key);
+        }
+    }
+
+    fn replace(&mut self, values: Vec<T>) {
+        self.items.clear();
+        self.members.clear();
+        for value in values {
+            let key = (self.key)(&value);
+            let handle = self.items.push((key.clone(), value, 0), 0);
+            let members = self.members.entry(key).or_default();
+            self.items.value_mut(handle).2 = members.push(handle, 0);
+        }
+
+        let entries = (0..self.items.len())
+            .map(|index| self.items.handle(index))
+            .filter(|handle| self.winner(&self.items.value(*handle).0) == *handle)
+            .map(|handle| {
+                let (key, value, _) = self.items.value(handle);
+                (key.clone(), value.clone())
+            })
+            .collect();
+        self.pending.push_back(MapDiff::Replace { entries });
+    }
+
+    fn clear(&mut self) {
+        self.items.clear();
+        self.members.clear();
+        self.pending.push_back(MapDiff::Clear {});
+    }
+}
+
+impl<S, F, K> SignalMap for SignalMapByKey<S, F, K>
+where
+    S: SignalVec,
+    S::Item: Clone,
+    F: FnMut(&S::Item) -> K,
+    K: Eq + Hash + Clone,
+{
+    type Key = K;
+    type Value = S::Item;
+
+    fn poll_map_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<MapDiff<Self::Key, Self::Value>>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(diff) = this.pending.pop_front() {
+                return Poll::Ready(Some(diff));
+            }
+
+            let diff = match this
+                .signal
+                .as_mut()
+                .as_pin_mut()
+                .map(|signal| signal.poll_vec_change(cx))
+            {
+                Some(Poll::Ready(Some(diff))) => diff,
+                Some(Poll::Ready(None)) => {
+                    this.signal.set(None);
+                    return Poll::Ready(None);
+                }
+                Some(Poll::Pending) => return Poll::Pending,
+                None => return Poll::Ready(None),
+            };
+
+            let mut keyed = Keyed {
+                key: this.key,
+                duplicates: *this.duplicates,
+                items: this.items,
+                members: this.members,
+                pending: this.pending,
+            };
+            match diff {
+                VecDiff::Replace { values } => keyed.replace(values),
+                VecDiff::InsertAt { index, value } => keyed.insert(index, value),
+                VecDiff::UpdateAt { index, value } => keyed.update(index, value),
+                VecDiff::RemoveAt { index } => keyed.remove(index),
+                VecDiff::Move {
+                    old_index,
+                    new_index,
+                } => keyed.move_item(old_index, new_index),
+                VecDiff::Push { value } => keyed.insert(keyed.items.len(), value),
+                VecDiff::Pop {} => keyed.remove(keyed.items.len() - 1),
+                VecDiff::Clear {} => keyed.clear(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::pin::pin;
+
+    use futures_signals::{signal_map::MapDiff, signal_vec::MutableVec};
+
+    use crate::{DuplicateKeys, SignalVecKeyedExt, testing::poll_map};
+
+    #[test]
+    fn duplicate_keys() {
+        let vec = MutableVec::new_with_values(vec![(1, 'a'), (2, 'b'), (1, 'c')]);
+        let mut first = pin!(
+            vec.signal_vec()
+                .to_signal_map_by_key(|item| item.0, DuplicateKeys::First)
+        );
+        let mut last = pin!(
+            vec.signal_vec()
+                .to_signal_map_by_key(|item| item.0, DuplicateKeys::Last)
+        );
+        assert_eq!(
+            poll_map(first.as_mut()),
+            vec![MapDiff::Replace {
+                entries: vec![(1, (1, 'a')), (2, (2, 'b'))]
+            }]
+        );
+        assert_eq!(
+            poll_map(last.as_mut()),
+            vec![MapDiff::Replace {
+                entries: vec![(2, (2, 'b')), (1, (1, 'c'))]
+            }]
+        );
+
+        vec.lock_mut().remove(0);
+        vec.lock_mut().push((3, 'd'));
+        assert_eq!(
+            poll_map(first.as_mut()),
+            vec![
+                MapDiff::Update {
+                    key: 1,
+                    value: (1, 'c')
+                },
+                MapDiff::Insert {
+                    key: 3,
+                    value: (3, 'd')
+                }
+            ]
+        );
+        assert_eq!(
+            poll_map(last.as_mut()),
+            vec![MapDiff::Insert {
+                key: 3,
+                value: (3, 'd')
+            }]
+        );
+
+        vec.lock_mut().set(0, (3, 'b'));
+        assert_eq!(
+            poll_map(first.as_mut()),
+            vec![
+                MapDiff::Remove { key: 2 },
+                MapDiff::Update {
+                    key: 3,
+                    value: (3, 'b')
+                }
+            ]
+        );
+        assert_eq!(poll_map(last.as_mut()), vec![MapDiff::Remove { key: 2 }]);
+    }
+
+    #[test]
+    fn duplicate_keys_moved() {
+        let vec = MutableVec::new_with_values(vec![(1, 'a'), (1, 'b'), (2, 'c')]);
+        let mut first = pin!(
+            vec.signal_vec()
+                .to_signal_map_by_key(|item| item.0, DuplicateKeys::First)
+        );
+        let mut last = pin!(
+            vec.signal_vec()
+                .to_signal_map_by_key(|item| item.0, DuplicateKeys::Last)
+        );
+        poll_map(first.as_mut());
+        poll_map(last.as_mut());
+
+        vec.lock_mut().move_from_to(0, 2);
+        assert_eq!(
+            poll_map(first.as_mut()),
+            vec![MapDiff::Update {
+                key: 1,
+                value: (1, 'b')
+            }]
+        );
+        assert_eq!(
+            poll_map(last.as_mut()),
+            vec![MapDiff::Update {
+                key: 1,
+                value: (1, 'a')
+            }]
+        );
+
+        // moved, but the values of the keys stay
+        vec.lock_mut().move_from_to(2, 1);
+        assert_eq!(poll_map(first.as_mut()), vec![]);
+        assert_eq!(poll_map(last.as_mut()), vec![]);
+
+        vec.lock_mut().insert(0, (1, 'd'));
+        vec.lock_mut().push((1, 'e'));
+        assert_eq!(
+            poll_map(first.as_mut()),
+            vec![MapDiff::Update {
+                key: 1,
+                value: (1, 'd')
+            }]
+        );
+        assert_eq!(
+            poll_map(last.as_mut()),
+            vec![MapDiff::Update {
+                key: 1,
+                value: (1, 'e')
+            }]
+        );
+    }
+}
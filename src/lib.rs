@@ -3,19 +3,79 @@ mod spawn;
 #[cfg(any(feature = "spawn", feature = "spawn-local"))]
 pub use spawn::*;
 
+mod aggregate;
+pub use aggregate::*;
+
+mod bounded;
+pub use bounded::*;
+
 mod entry;
 pub use entry::*;
 
+#[cfg(any(feature = "spawn", feature = "spawn-local"))]
+mod expiring;
+#[cfg(any(feature = "spawn", feature = "spawn-local"))]
+pub use expiring::*;
+
 mod ext;
 pub use ext::*;
 
+mod filter;
+pub use filter::*;
+
+mod find;
+pub use find::*;
+
 mod flatten;
 pub use flatten::*;
 
+mod frame;
+pub use frame::*;
+
+mod group;
+pub use group::*;
+
+mod history;
+pub use history::*;
+
+mod indexed;
+pub use indexed::*;
+
+mod join;
+pub use join::*;
+
+mod keyed;
+pub use keyed::*;
+
+mod partition;
+pub use partition::*;
+
+mod reshape;
+pub use reshape::*;
+
+mod sequence;
+
+mod set;
+pub use set::*;
+
+mod sort;
+pub use sort::*;
+
+mod transaction;
+pub use transaction::*;
+
+mod version;
+
+mod window;
+pub use window::*;
+
 #[cfg(feature = "option")]
 mod option;
 #[cfg(feature = "option")]
 pub use option::*;
 
+#[cfg(test)]
+mod testing;
+
 #[cfg(all(target_arch = "wasm32", feature = "spawn"))]
 compile_error!("'spawn' feature is not available for 'wasm32'");
@@ -0,0 +1,304 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+};
+
+use futures_signals::signal_vec::{SignalVec, VecDiff};
+
+use crate::filter::Visibility;
+
+const MATCHING: usize = 0;
+const REST: usize = 1;
+
+// Wakers of both sides, the source is polled with a waker waking both.
+#[derive(Debug, Default)]
+struct Wakers(Mutex<[Option<Waker>; 2]>);
+
+impl Wakers {
+    fn register(&self, side: usize, waker: &Waker) {
+        let mut wakers = self.0.lock().unwrap();
+        match &mut wakers[side] {
+            Some(registered) if registered.will_wake(waker) => {}
+            registered => *registered = Some(waker.clone()),
+        }
+    }
+
+    fn wake_side(&self, side: usize) {
+        if let Some(waker) = self.0.lock().unwrap()[side].take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Wake for Wakers {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        let wakers = self.0.lock().unwrap().each_mut().map(Option::take);
+        for waker in wakers.into_iter().flatten() {
+            waker.wake();
+        }
+    }
+}
+
+struct State<S: SignalVec, P> {
+    signal: Option<Pin<Box<S>>>,
+    predicate: P,
+    // source items, to replace the diffs queued for a side not keeping up
+    items: Vec<S::Item>,
+    sides: [Visibility; 2],
+    pending: [VecDeque<VecDiff<S::Item>>; 2],
+    // false once the side is dropped
+    live: [bool; 2],
+}
+
+impl<S, P> State<S, P>
+where
+    S: SignalVec,
+    S::Item: Clone,
+    P: FnMut(&S::Item) -> bool,
+{
+    fn apply(&mut self, diff: VecDiff<S::Item>) {
+        diff.clone().apply_to_vec(&mut self.items);
+        self.split(diff);
+
+        // the queue of a side is kept shorter than the source
+        for side in [MATCHING, REST] {
+            let pending = &mut self.pending[side];
+            if !self.live[side] {
+                pending.clear();
+            } else if pending.len() > self.items.len() {
+                pending.clear();
+                pending.push_back(VecDiff::Replace {
+                    values: self.sides[side].visible(&self.items).cloned().collect(),
+                });
+            }
+        }
+    }
+
+    fn split(&mut self, diff: VecDiff<S::Item>) {
+        let [matching, rest] = &mut self.sides;
+        let [matching_pending, rest_pending] = &mut self.pending;
+        let len = matching.len();
+        match diff {
+            VecDiff::Replace { values } => {
+                let matches = values.iter().map(&mut self.predicate).collect::<Vec<_>>();
+                let sides = matches.iter().zip(values);
+                matching.replace(
+                    sides.clone().map(|(matched, item)| (*matched, item)),
+                    matching_pending,
+                );
+                rest.replace(sides.map(|(matched, item)| (!matched, item)), rest_pending);
+            }
+            VecDiff::InsertAt { index, value } => {
+                let matched = (self.predicate)(&value);
+                matching.insert(index, matched, value.clone(), matching_pending);
+                rest.insert(index, !matched, value, rest_pending);
+            }
+            VecDiff::UpdateAt { index, value } => {
+                let matched = (self.predicate)(&value);
+                matching.update(index, matched, value.clone(), matching_pending);
+                rest.update(index, !matched, value, rest_pending);
+            }
+            VecDiff::RemoveAt { index } => {
+                matching.remove(index, matching_pending);
+                rest.remove(index, rest_pending);
+            }
+            VecDiff::Move {
+                old_index,
+                new_index,
+            } => {
+                matching.move_item(old_index, new_index, matching_pending);
+                rest.move_item(old_index, new_index, rest_pending);
+            }
+            VecDiff::Push { value } => {
+                let matched = (self.predicate)(&value);
+                matching.insert(len, matched, value.clone(), matching_pending);
+                rest.insert(len, !matched, value, rest_pending);
+            }
+            VecDiff::Pop {} => {
+                matching.remove(len - 1, matching_pending);
+                rest.remove(len - 1, rest_pending);
+            }
+            VecDiff::Clear {} => {
+                matching.clear(matching_pending);
+                rest.clear(rest_pending);
+            }
+        }
+    }
+}
+
+/// One side of `SignalVecFilterExt::partition`, both sides share the source
+/// subscription. Diffs queued for a side lagging behind are replaced by `Replace`
+/// once they outnumber the source items, nothing is queued for a dropped side.
+#[must_use = "SignalVecs do nothing unless polled"]
+pub struct Partition<S: SignalVec, P> {
+    state: Arc<Mutex<State<S, P>>>,
+    wakers: Arc<Wakers>,
+    side: usize,
+}
+
+impl<S: SignalVec, P> fmt::Debug for Partition<S, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Partition")
+            .field("matching", &(self.side == MATCHING))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Items matching the predicate, and the rest.
+pub(crate) fn partition<S: SignalVec, P>(
+    signal: S,
+    predicate: P,
+) -> (Partition<S, P>, Partition<S, P>) {
+    let state = Arc::new(Mutex::new(State {
+        signal: Some(Box::pin(signal)),
+        predicate,
+        items: vec![],
+        sides: Default::default(),
+        pending: Default::default(),
+        live: [true; 2],
+    }));
+    let wakers = Arc::new(Wakers::default());
+    (
+        Partition {
+            state: state.clone(),
+            wakers: wakers.clone(),
+            side: MATCHING,
+        },
+        Partition {
+            state,
+            wakers,
+            side: REST,
+        },
+    )
+}
+
+impl<S: SignalVec, P> Drop for Partition<S, P> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.live[self.side] = false;
+            state.pending[self.side].clear();
+        }
+    }
+}
+
+impl<S, P> SignalVec for Partition<S, P>
+where
+    S: SignalVec,
+    S::Item: Clone,
+    P: FnMut(&S::Item) -> bool,
+{
+    type Item = S::Item;
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let side = self.side;
+        self.wakers.register(side, cx.waker());
+        let waker = Waker::from(self.wakers.clone());
+        let mut state = self.state.lock().unwrap();
+
+        loop {
+            if let Some(diff) = state.pending[side].pop_front() {
+                return Poll::Ready(Some(diff));
+            }
+
+            let diff = match state.signal.as_mut().map(|signal| {
+                signal
+                    .as_mut()
+                    .poll_vec_change(&mut Context::from_waker(&waker))
+            }) {
+                Some(Poll::Ready(Some(diff))) => diff,
+                Some(Poll::Ready(None)) => {
+                    state.signal = None;
+                    self.wakers.wake_side(1 - side);
+                    return Poll::Ready(None);
+                }
+                Some(Poll::Pending) => return Poll::Pending,
+                None => return Poll::Ready(None),
+            };
+
+            state.apply(diff);
+            if !state.pending[1 - side].is_empty() {
+                self.wakers.wake_side(1 - side);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::pin::pin;
+
+    use futures_signals::signal_vec::{MutableVec, VecDiff};
+
+    use super::REST;
+    use crate::{SignalVecFilterExt, testing::poll_vec};
+
+    #[test]
+    fn partition() {
+        let vec = MutableVec::new_with_values(vec![1, 2, 3]);
+        let (odd, even) = vec.signal_vec().partition(|item| item % 2 == 1);
+        let (mut odd, mut even) = (pin!(odd), pin!(even));
+        assert_eq!(
+            poll_vec(odd.as_mut()),
+            vec![VecDiff::Replace { values: vec![1, 3] }]
+        );
+
+        vec.lock_mut().set(0, 4);
+        vec.lock_mut().push(5);
+        assert_eq!(
+            poll_vec(odd.as_mut()),
+            vec![
+                VecDiff::RemoveAt { index: 0 },
+                VecDiff::InsertAt { index: 1, value: 5 }
+            ]
+        );
+        assert_eq!(
+            poll_vec(even.as_mut()),
+            vec![
+                VecDiff::Replace { values: vec![2] },
+                VecDiff::InsertAt { index: 0, value: 4 }
+            ]
+        );
+    }
+
+    #[test]
+    fn unpolled_side() {
+        let vec = MutableVec::new_with_values(vec![1, 2]);
+        let (odd, even) = vec.signal_vec().partition(|item| item % 2 == 1);
+        let (mut odd, mut even) = (pin!(odd), pin!(even));
+        poll_vec(odd.as_mut());
+
+        for item in 3..10 {
+            vec.lock_mut().push(item);
+            vec.lock_mut().remove(0);
+            poll_vec(odd.as_mut());
+        }
+        assert_eq!(
+            poll_vec(even.as_mut()),
+            vec![VecDiff::Replace { values: vec![8] }]
+        );
+    }
+
+    #[test]
+    fn dropped_side() {
+        let vec = MutableVec::new_with_values(vec![1, 2]);
+        let (odd, even) = vec.signal_vec().partition(|item| item % 2 == 1);
+        let mut odd = pin!(odd);
+        drop(even);
+
+        for item in 3..10 {
+            vec.lock_mut().push(item);
+            poll_vec(odd.as_mut());
+        }
+        assert!(odd.state.lock().unwrap().pending[REST].is_empty());
+    }
+}
@@ -0,0 +1,460 @@
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_signals::signal_vec::{SignalVec, VecDiff};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    #[derive(Debug)]
+    #[must_use = "SignalVecs do nothing unless polled"]
+    pub struct Reverse<S> {
+        #[pin]
+        signal: S,
+        len: usize,
+    }
+}
+
+impl<S> Reverse<S> {
+    pub(crate) fn new(signal: S) -> Self {
+        Self { signal, len: 0 }
+    }
+}
+
+impl<S: SignalVec> SignalVec for Reverse<S> {
+    type Item = S::Item;
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let this = self.project();
+        let len = this.len;
+        this.signal.poll_vec_change(cx).map(|diff| {
+            diff.map(|diff| match diff {
+                VecDiff::Replace { mut values } => {
+                    *len = values.len();
+                    values.reverse();
+                    VecDiff::Replace { values }
+                }
+                VecDiff::InsertAt { index, value } => {
+                    *len += 1;
+                    VecDiff::InsertAt {
+                        index: *len - 1 - index,
+                        value,
+                    }
+                }
+                VecDiff::UpdateAt { index, value } => VecDiff::UpdateAt {
+                    index: *len - 1 - index,
+                    value,
+                },
+                VecDiff::RemoveAt { index } => {
+                    *len -= 1;
+                    VecDiff::RemoveAt {
+                        index: *len - index,
+                    }
+                }
+                VecDiff::Move {
+                    old_index,
+                    new_index,
+                } => VecDiff::Move {
+                    old_index: *len - 1 - old_index,
+                    new_index: *len - 1 - new_index,
+                },
+                VecDiff::Push { value } => {
+                    *len += 1;
+                    VecDiff::InsertAt { index: 0, value }
+                }
+                VecDiff::Pop {} => {
+                    *len -= 1;
+                    VecDiff::RemoveAt { index: 0 }
+                }
+                VecDiff::Clear {} => {
+                    *len = 0;
+                    VecDiff::Clear {}
+                }
+            })
+        })
+    }
+}
+
+pin_project! {
+    /// Chunks of `size` items, the last one may be shorter. Changed chunks are
+    /// emitted as `UpdateAt`.
+    #[derive(Debug)]
+    #[must_use = "SignalVecs do nothing unless polled"]
+    pub struct Chunks<S>
+    where
+        S: SignalVec,
+    {
+        #[pin]
+        signal: S,
+        size: usize,
+        items: Vec<S::Item>,
+        pending: VecDeque<VecDiff<Vec<S::Item>>>,
+    }
+}
+
+impl<S: SignalVec> Chunks<S> {
+    pub(crate) fn new(signal: S, size: usize) -> Self {
+        assert!(size != 0, "chunk size must be non-zero");
+        Self {
+            signal,
+            size,
+            items: vec![],
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+fn chunk<T: Clone>(items: &[T], size: usize, index: usize) -> Vec<T> {
+    items[index * size..items.len().min((index + 1) * size)].to_vec()
+}
+
+impl<S> SignalVec for Chunks<S>
+where
+    S: SignalVec,
+    S::Item: Clone,
+{
+    type Item = Vec<S::Item>;
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let mut this = self.project();
+        let size = *this.size;
+
+        loop {
+            if let Some(diff) = this.pending.pop_front() {
+                return Poll::Ready(Some(diff));
+            }
+
+            let diff = match this.signal.as_mut().poll_vec_change(cx) {
+                Poll::Ready(Some(diff)) => diff,
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+
+            let old_count = this.items.len().div_ceil(size);
+            // first and last changed item
+            let changed = match diff {
+                VecDiff::Replace { values } => {
+                    *this.items = values;
+                    let values = (0..this.items.len().div_ceil(size))
+                        .map(|index| chunk(this.items, size, index))
+                        .collect();
+                    this.pending.push_back(VecDiff::Replace { values });
+                    continue;
+                }
+                VecDiff::InsertAt { index, value } => {
+                    this.items.insert(index, value);
+                    (index, this.items.len())
+                }
+                VecDiff::UpdateAt { index, value } => {
+                    this.items[index] = value;
+                    (index, index + 1)
+                }
+                VecDiff::RemoveAt { index } => {
+                    this.items.remove(index);
+                    (index, this.items.len())
+                }
+                VecDiff::Move {
+                    old_index,
+                    new_index,
+                } => {
+                    let item = this.items.remove(old_index);
+                    this.items.insert(new_index, item);
+                    (old_index.min(new_index), old_index.max(new_index) + 1)
+                }
+                VecDiff::Push { value } => {
+                    this.items.push(value);
+                    (this.items.len() - 1, this.items.len())
+                }
+                VecDiff::Pop {} => {
+                    this.items.pop();
+                    (this.items.len(), this.items.len())
+                }
+                VecDiff::Clear {} => {
+                    this.items.clear();
+                    this.pending.push_back(VecDiff::Clear {});
+                    continue;
+                }
+            };
+
+            let count = this.items.len().div_ceil(size);
+            let (from, to) = (changed.0 / size, changed.1.div_ceil(size));
+            for index in from..to.min(count).min(old_count) {
+                this.pending.push_back(VecDiff::UpdateAt {
+                    index,
+                    value: chunk(this.items, size, index),
+                });
+            }
+            if count > old_count {
+                this.pending.push_back(VecDiff::Push {
+                    value: chunk(this.items, size, count - 1),
+                });
+            } else if count < old_count {
+                this.pending.push_back(VecDiff::Pop {});
+            }
+        }
+    }
+}
+
+/// Item with its previous and next item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Neighbors<T> {
+    pub previous: Option<T>,
+    pub item: T,
+    pub next: Option<T>,
+}
+
+pin_project! {
+    /// Items with changed neighbors are emitted as `UpdateAt`.
+    #[derive(Debug)]
+    #[must_use = "SignalVecs do nothing unless polled"]
+    pub struct WithNeighbors<S>
+    where
+        S: SignalVec,
+    {
+        #[pin]
+        signal: S,
+        items: Vec<S::Item>,
+        pending: VecDeque<VecDiff<Neighbors<S::Item>>>,
+    }
+}
+
+impl<S: SignalVec> WithNeighbors<S> {
+    pub(crate) fn new(signal: S) -> Self {
+        Self {
+            signal,
+            items: vec![],
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+fn neighbors<T: Clone>(items: &[T], index: usize) -> Neighbors<T> {
+    Neighbors {
+        previous: index.checked_sub(1).map(|index| items[index].clone()),
+        item: items[index].clone(),
+        next: items.get(index + 1).cloned(),
+    }
+}
+
+impl<S> SignalVec for WithNeighbors<S>
+where
+    S: SignalVec,
+    S::Item: Clone,
+{
+    type Item = Neighbors<S::Item>;
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(diff) = this.pending.pop_front() {
+                return Poll::Ready(Some(diff));
+            }
+
+            let diff = match this.signal.as_mut().poll_vec_change(cx) {
+                Poll::Ready(Some(diff)) => diff,
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+
+            let items = &mut *this.items;
+            // indexes of items with changed neighbors
+            let updated = match diff {
+                VecDiff::Replace { values } => {
+                    *items = values;
+                    let values = (0..items.len())
+                        .map(|index| neighbors(items, index))
+                        .collect();
+                    this.pending.push_back(VecDiff::Replace { values });
+                    vec![]
+                }
+                VecDiff::InsertAt { index, value } => {
+                    items.insert(index, value);
+                    this.pending.push_back(VecDiff::InsertAt {
+                        index,
+                        value: neighbors(items, index),
+                    });
+                    vec![index.wrapping_sub(1), index + 1]
+                }
+                VecDiff::UpdateAt { index, value } => {
+                    items[index] = value;
+                    vec![index.wrapping_sub(1), index, index + 1]
+                }
+                VecDiff::RemoveAt { index } => {
+                    items.remove(index);
+                    this.pending.push_back(VecDiff::RemoveAt { index });
+                    vec![index.wrapping_sub(1), index]
+                }
+                VecDiff::Move {
+                    old_index,
+                    new_index,
+                } => {
+                    let item = items.remove(old_index);
+                    items.insert(new_index, item);
+                    this.pending.push_back(VecDiff::Move {
+                        old_index,
+                        new_index,
+                    });
+                    let mut updated = vec![];
+                    for index in [old_index, new_index] {
+                        updated.extend([index.wrapping_sub(1), index, index + 1]);
+                    }
+                    updated.sort_unstable();
+                    updated.dedup();
+                    updated
+                }
+                VecDiff::Push { value } => {
+                    items.push(value);
+                    let index = items.len() - 1;
+                    this.pending.push_back(VecDiff::Push {
+                        value: neighbors(items, index),
+                    });
+                    vec![index.wrapping_sub(1)]
+                }
+                VecDiff::Pop {} => {
+                    items.pop();
+                    this.pending.push_back(VecDiff::Pop {});
+                    vec![items.len().wrapping_sub(1)]
+                }
+                VecDiff::Clear {} => {
+                    items.clear();
+                    this.pending.push_back(VecDiff::Clear {});
+                    vec![]
+                }
+            };
+
+            // wrapped around indexes are out of bounds as well
+            for index in updated.into_iter().filter(|index| *index < items.len()) {
+                this.pending.push_back(VecDiff::UpdateAt {
+                    index,
+                    value: neighbors(items, index),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::pin::pin;
+
+    use futures_signals::signal_vec::{MutableVec, SignalVecExt, VecDiff};
+
+    use crate::{
+        Neighbors, SignalVecReshapeExt,
+        testing::{poll_signal, poll_vec},
+    };
+
+    #[test]
+    fn reverse_chunks() {
+        let vec = MutableVec::new_with_values(vec![1, 2, 3, 4, 5]);
+        let mut reversed = pin!(vec.signal_vec().reverse().to_signal_cloned());
+        let mut chunks = pin!(vec.signal_vec().chunks(2));
+        let mut check = |expected: Vec<i32>| {
+            assert_eq!(poll_signal(reversed.as_mut()).pop(), Some(expected));
+        };
+        check(vec![5, 4, 3, 2, 1]);
+        assert_eq!(
+            poll_vec(chunks.as_mut()),
+            vec![VecDiff::Replace {
+                values: vec![vec![1, 2], vec![3, 4], vec![5]]
+            }]
+        );
+
+        vec.lock_mut().insert(1, 6);
+        check(vec![5, 4, 3, 2, 6, 1]);
+        vec.lock_mut().move_from_to(0, 3);
+        check(vec![5, 4, 1, 3, 2, 6]);
+        vec.lock_mut().pop();
+        check(vec![4, 1, 3, 2, 6]);
+        vec.lock_mut().remove(1);
+        check(vec![4, 1, 3, 6]);
+        assert_eq!(
+            poll_vec(chunks.as_mut()),
+            vec![
+                VecDiff::UpdateAt {
+                    index: 0,
+                    value: vec![1, 6]
+                },
+                VecDiff::UpdateAt {
+                    index: 1,
+                    value: vec![2, 3]
+                },
+                VecDiff::UpdateAt {
+                    index: 2,
+                    value: vec![4, 5]
+                },
+                VecDiff::UpdateAt {
+                    index: 0,
+                    value: vec![6, 2]
+                },
+                VecDiff::UpdateAt {
+                    index: 1,
+                    value: vec![3, 1]
+                },
+                VecDiff::UpdateAt {
+                    index: 2,
+                    value: vec![4]
+                },
+                VecDiff::UpdateAt {
+                    index: 0,
+                    value: vec![6, 3]
+                },
+                VecDiff::UpdateAt {
+                    index: 1,
+                    value: vec![1, 4]
+                },
+                VecDiff::Pop {},
+            ]
+        );
+    }
+
+    #[test]
+    fn with_neighbors() {
+        let vec = MutableVec::new_with_values(vec![1, 2]);
+        let mut neighbors = pin!(vec.signal_vec().with_neighbors());
+        poll_vec(neighbors.as_mut());
+
+        vec.lock_mut().insert(1, 3);
+        assert_eq!(
+            poll_vec(neighbors.as_mut()),
+            vec![
+                VecDiff::InsertAt {
+                    index: 1,
+                    value: Neighbors {
+                        previous: Some(1),
+                        item: 3,
+                        next: Some(2)
+                    }
+                },
+                VecDiff::UpdateAt {
+                    index: 0,
+                    value: Neighbors {
+                        previous: None,
+                        item: 1,
+                        next: Some(3)
+                    }
+                },
+                VecDiff::UpdateAt {
+                    index: 2,
+                    value: Neighbors {
+                        previous: Some(3),
+                        item: 2,
+                        next: None
+                    }
+                },
+            ]
+        );
+    }
+}
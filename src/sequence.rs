@@ -0,0 +1,402 @@
+const NIL: usize = usize::MAX;
+
+#[derive(Debug)]
+struct Node<T> {
+    value: Option<T>,
+    priority: u64,
+    parent: usize,
+    left: usize,
+    right: usize,
+    size: usize,
+    weight: usize,
+    total: usize,
+}
+
+/// Items addressed by position, with insert, remove and move at any position in
+/// O(log n). Every item has a weight, prefix sums of weights are O(log n) too.
+/// Items have stable handles, valid until the item is removed.
+#[derive(Debug)]
+pub(crate) struct Sequence<T> {
+    // implicit treap, slots of removed nodes are reused
+    nodes: Vec<Node<T>>,
+    free: Vec<usize>,
+    root: usize,
+    seed: u64,
+}
+
+impl<T> Default for Sequence<T> {
+    fn default() -> Self {
+        Self {
+            nodes: vec![],
+            free: vec![],
+            root: NIL,
+            seed: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+}
+
+impl<T> Sequence<T> {
+    pub(crate) fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    /// Sum of all weights.
+    pub(crate) fn total(&self) -> usize {
+        self.subtotal(self.root)
+    }
+
+    /// Sum of weights of the first `index` items.
+    pub(crate) fn prefix(&self, mut index: usize) -> usize {
+        let mut sum = 0;
+        let mut node = self.root;
+        while node != NIL && index > 0 {
+            let left = self.nodes[node].left;
+            let left_size = self.size(left);
+            if index <= left_size {
+                node = left;
+            } else {
+                sum += self.subtotal(left) + self.nodes[node].weight;
+                index -= left_size + 1;
+                node = self.nodes[node].right;
+            }
+        }
+        sum
+    }
+
+    /// Index of the first item with non-zero weight.
+    pub(crate) fn first_weighted(&self) -> Option<usize> {
+        let mut index = 0;
+        let mut node = self.root;
+        while self.subtotal(node) > 0 {
+            let left = self.nodes[node].left;
+            if self.subtotal(left) > 0 {
+                node = left;
+            } else if self.nodes[node].weight > 0 {
+                return Some(index + self.size(left));
+            } else {
+                index += self.size(left) + 1;
+                node = self.nodes[node].right;
+            }
+        }
+        None
+    }
+
+    pub(crate) fn handle(&self, mut index: usize) -> usize {
+        let mut node = self.root;
+        loop {
+            let left = self.nodes[node].left;
+            let left_size = self.size(left);
+            if index < left_size {
+                node = left;
+            } else if index == left_size {
+                return node;
+            } else {
+                index -= left_size + 1;
+                node = self.nodes[node].right;
+            }
+        }
+    }
+
+    pub(crate) fn index_of(&self, handle: usize) -> usize {
+        let mut index = self.size(self.nodes[handle].left);
+        let mut node = handle;
+        while self.nodes[node].parent != NIL {
+            let parent = self.nodes[node].parent;
+            if self.nodes[parent].right == node {
+                index += self.size(self.nodes[parent].left) + 1;
+            }
+            node = parent;
+        }
+        index
+    }
+
+    /// Number of leading items matching `pred`, which must hold for a prefix of
+    /// the items.
+    pub(crate) fn partition_point(&self, mut pred: impl FnMut(&T) -> bool) -> usize {
+        let mut index = 0;
+        let mut node = self.root;
+        while node != NIL {
+            if pred(self.value(node)) {
+                index += self.size(self.nodes[node].left) + 1;
+                node = self.nodes[node].right;
+            } else {
+                node = self.nodes[node].left;
+            }
+        }
+        index
+    }
+
+    /// Handles of all items in order.
+    pub(crate) fn handles(&self) -> Vec<usize> {
+        let mut handles = Vec::with_capacity(self.len());
+        let mut stack = vec![];
+        let mut node = self.root;
+        while node != NIL || !stack.is_empty() {
+            while node != NIL {
+                stack.push(node);
+                node = self.nodes[node].left;
+            }
+            node = stack.pop().expect("pushed node");
+            handles.push(node);
+            node = self.nodes[node].right;
+        }
+        handles
+    }
+
+    pub(crate) fn value(&self, handle: usize) -> &T {
+        self.nodes[handle].value.as_ref().expect("valid handle")
+    }
+
+    pub(crate) fn value_mut(&mut self, handle: usize) -> &mut T {
+        self.nodes[handle].value.as_mut().expect("valid handle")
+    }
+
+    pub(crate) fn get(&self, index: usize) -> &T {
+        self.value(self.handle(index))
+    }
+
+    pub(crate) fn get_mut(&mut self, index: usize) -> &mut T {
+        let handle = self.handle(index);
+        self.value_mut(handle)
+    }
+
+    pub(crate) fn set_weight(&mut self, index: usize, weight: usize) {
+        let mut node = self.handle(index);
+        if self.nodes[node].weight == weight {
+            return;
+        }
+        self.nodes[node].weight = weight;
+        while node != NIL {
+            self.update(node);
+            node = self.nodes[node].parent;
+        }
+    }
+
+    /// Returns handle of the inserted item.
+    pub(crate) fn insert(&mut self, index: usize, value: T, weight: usize) -> usize {
+        let node = self.alloc(value, weight);
+        self.attach(node, index);
+        node
+    }
+
+    pub(crate) fn push(&mut self, value: T, weight: usize) -> usize {
+        self.insert(self.len(), value, weight)
+    }
+
+    pub(crate) fn remove(&mut self, index: usize) -> T {
+        let node = self.detach(index);
+        self.free.push(node);
+        self.nodes[node].value.take().expect("valid handle")
+    }
+
+    /// The moved item keeps its handle.
+    pub(crate) fn move_item(&mut self, old_index: usize, new_index: usize) {
+        let node = self.detach(old_index);
+        self.attach(node, new_index);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.nodes.clear();
+        self.free.clear();
+        self.root = NIL;
+    }
+
+    fn size(&self, node: usize) -> usize {
+        if node == NIL {
+            0
+        } else {
+            self.nodes[node].size
+        }
+    }
+
+    fn subtotal(&self, node: usize) -> usize {
+        if node == NIL {
+            0
+        } else {
+            self.nodes[node].total
+        }
+    }
+
+    fn update(&mut self, node: usize) {
+        let Node {
+            left,
+            right,
+            weight,
+            ..
+        } = self.nodes[node];
+        self.nodes[node].size = 1 + self.size(left) + self.size(right);
+        self.nodes[node].total = weight + self.subtotal(left) + self.subtotal(right);
+        for child in [left, right] {
+            if child != NIL {
+                self.nodes[child].parent = node;
+            }
+        }
+    }
+
+    fn alloc(&mut self, value: T, weight: usize) -> usize {
+        // xorshift
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        let node = Node {
+            value: Some(value),
+            priority: self.seed,
+            parent: NIL,
+            left: NIL,
+            right: NIL,
+            size: 1,
+            weight,
+            total: weight,
+        };
+        match self.free.pop() {
+            Some(slot) => {
+                self.nodes[slot] = node;
+                slot
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn attach(&mut self, node: usize, index: usize) {
+        let (left, right) = self.split(self.root, index);
+        let left = self.merge(left, node);
+        let root = self.merge(left, right);
+        self.set_root(root);
+    }
+
+    fn detach(&mut self, index: usize) -> usize {
+        let (left, right) = self.split(self.root, index);
+        let (node, right) = self.split(right, 1);
+        let root = self.merge(left, right);
+        self.set_root(root);
+        self.nodes[node].parent = NIL;
+        node
+    }
+
+    fn set_root(&mut self, root: usize) {
+        self.root = root;
+        if root != NIL {
+            self.nodes[root].parent = NIL;
+        }
+    }
+
+    // first `index` items on the left
+    fn split(&mut self, node: usize, index: usize) -> (usize, usize) {
+        if node == NIL {
+            return (NIL, NIL);
+        }
+
+        let left = self.nodes[node].left;
+        let left_size = self.size(left);
+        if index <= left_size {
+            let (first, second) = self.split(left, index);
+            self.nodes[node].left = second;
+            self.update(node);
+            (first, node)
+        } else {
+            let right = self.nodes[node].right;
+            let (first, second) = self.split(right, index - left_size - 1);
+            self.nodes[node].right = first;
+            self.update(node);
+            (node, second)
+        }
+    }
+
+    fn merge(&mut self, first: usize, second: usize) -> usize {
+        if first == NIL {
+            return second;
+        }
+        if second == NIL {
+            return first;
+        }
+
+        if self.nodes[first].priority > self.nodes[second].priority {
+            let right = self.nodes[first].right;
+            self.nodes[first].right = self.merge(right, second);
+            self.update(first);
+            first
+        } else {
+            let left = self.nodes[second].left;
+            self.nodes[second].left = self.merge(first, left);
+            self.update(second);
+            second
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Sequence;
+
+    #[test]
+    fn matches_vec() {
+        let mut sequence = Sequence::default();
+        let mut reference: Vec<(u32, usize)> = vec![];
+        let mut handles = vec![];
+        let mut seed = 7u32;
+        let mut random = |bound: usize| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 8) as usize % bound
+        };
+
+        for step in 0..2000 {
+            let len = reference.len();
+            match random(6) {
+                0 | 1 => {
+                    let index = random(len + 1);
+                    let weight = random(5);
+                    let handle = sequence.insert(index, step, weight);
+                    reference.insert(index, (step, weight));
+                    handles.push((handle, step));
+                }
+                2 if len > 0 => {
+                    let index = random(len);
+                    assert_eq!(sequence.remove(index), reference.remove(index).0);
+                }
+                3 if len > 0 => {
+                    let (old_index, new_index) = (random(len), random(len));
+                    sequence.move_item(old_index, new_index);
+                    let item = reference.remove(old_index);
+                    reference.insert(new_index, item);
+                }
+                4 if len > 0 => {
+                    let index = random(len);
+                    let weight = random(5);
+                    sequence.set_weight(index, weight);
+                    reference[index].1 = weight;
+                }
+                _ => {}
+            }
+
+            assert_eq!(sequence.len(), reference.len());
+            let index = random(reference.len() + 1);
+            let prefix = reference[..index].iter().map(|item| item.1).sum::<usize>();
+            assert_eq!(sequence.prefix(index), prefix);
+            assert_eq!(
+                sequence.total(),
+                reference.iter().map(|item| item.1).sum::<usize>()
+            );
+            assert_eq!(
+                sequence.first_weighted(),
+                reference.iter().position(|item| item.1 > 0)
+            );
+        }
+
+        for (index, item) in reference.iter().enumerate() {
+            assert_eq!(*sequence.get(index), item.0);
+        }
+        for (handle, value) in handles {
+            if let Some(index) = reference.iter().position(|item| item.0 == value) {
+                assert_eq!(*sequence.value(handle), value);
+                assert_eq!(sequence.index_of(handle), index);
+            }
+        }
+        for split in 0..=reference.len() {
+            let before = |value: &u32| reference[..split].iter().any(|item| item.0 == *value);
+            assert_eq!(sequence.partition_point(before), split);
+        }
+    }
+}
@@ -0,0 +1,816 @@
+use std::{
+    collections::VecDeque,
+    hash::Hash,
+    iter, mem,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_signals::signal_vec::{SignalVec, VecDiff};
+use pin_project_lite::pin_project;
+
+use crate::{
+    ext::{HashMap, collect_hash_map},
+    filter::Visibility,
+    sequence::Sequence,
+};
+
+const LEFT: usize = 0;
+const RIGHT: usize = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SetOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+// Items of both sides with the items of each key. The output is the visible left
+// items followed by the visible right items.
+#[derive(Debug)]
+struct Sides<K, T> {
+    operation: SetOperation,
+    // items with their key and the handle among the items with the key
+    items: [Sequence<(K, T, usize)>; 2],
+    // handles of the items with the key, a key without items is absent
+    members: [HashMap<K, Sequence<usize>>; 2],
+    visibility: [Visibility; 2],
+}
+
+impl<K, T> Sides<K, T>
+where
+    K: Eq + Hash + Clone,
+    T: Clone,
+{
+    fn visible(&self, side: usize, key: &K) -> bool {
+        let other = self.members[1 - side].contains_key(key);
+        match (self.operation, side) {
+            (SetOperation::Union, LEFT) => true,
+            (SetOperation::Union, _) => !other,
+            (SetOperation::Intersection, LEFT) => other,
+            (SetOperation::Difference, LEFT) => !other,
+            _ => false,
+        }
+    }
+
+    // diffs of one side, offset by the visible left items for the right side
+    fn emit(
+        &self,
+        side: usize,
+        diffs: &mut VecDeque<VecDiff<T>>,
+        pending: &mut VecDeque<VecDiff<T>>,
+    ) {
+        let offset = if side == RIGHT {
+            self.visibility[LEFT].visible_len()
+        } else {
+            0
+        };
+        pending.extend(diffs.drain(..).map(|diff| match diff {
+            VecDiff::InsertAt { index, value } => VecDiff::InsertAt {
+                index: index + offset,
+                value,
+            },
+            VecDiff::UpdateAt { index, value } => VecDiff::UpdateAt {
+                index: index + offset,
+                value,
+            },
+            VecDiff::RemoveAt { index } => VecDiff::RemoveAt {
+                index: index + offset,
+            },
+            VecDiff::Move {
+                old_index,
+                new_index,
+            } => VecDiff::Move {
+                old_index: old_index + offset,
+                new_index: new_index + offset,
+            },
+            diff => diff,
+        }));
+    }
+
+    fn apply<F>(
+        &mut self,
+        side: usize,
+        diff: VecDiff<T>,
+        key: &mut F,
+        pending: &mut VecDeque<VecDiff<T>>,
+    ) where
+        F: FnMut(&T) -> K,
+    {
+        match diff {
+            VecDiff::Replace { values } => self.replace(side, values, key, pending),
+            VecDiff::InsertAt { index, value } => {
+                self.insert(side, index, key(&value), value, pending)
+            }
+            VecDiff::UpdateAt { index, value } => {
+                let key = key(&value);
+                if key == self.items[side].get(index).0 {
+                    self.items[side].get_mut(index).1 = value.clone();
+                    let mut diffs = VecDeque::new();
+                    let visible = self.visible(side, &key);
+                    self.visibility[side].update(index, visible, value, &mut diffs);
+                    self.emit(side, &mut diffs, pending);
+                } else {
+                    self.remove(side, index, pending);
+                    self.insert(side, index, key, value, pending);
+                }
+            }
+            VecDiff::RemoveAt { index } => self.remove(side, index, pending),
+            VecDiff::Move {
+                old_index,
+                new_index,
+            } => {
+                self.items[side].move_item(old_index, new_index);
+                let mut diffs = VecDeque::new();
+                self.visibility[side].move_item(old_index, new_index, &mut diffs);
+                self.emit(side, &mut diffs, pending);
+            }
+            VecDiff::Push { value } => {
+                let index = self.items[side].len();
+                self.insert(side, index, key(&value), value, pending);
+            }
+            VecDiff::Pop {} => self.remove(side, self.items[side].len() - 1, pending),
+            VecDiff::Clear {} => self.replace(side, vec![], key, pending),
+        }
+    }
+
+    fn insert(
+        &mut self,
+        side: usize,
+        index: usize,
+        key: K,
+        item: T,
+        pending: &mut VecDeque<VecDiff<T>>,
+    ) {
+        let handle = self.items[side].insert(index, (key.clone(), item.clone(), 0), 0);
+        let members = self.members[side].entry(key.clone()).or_default();
+        let first = members.len() == 0;
+        self.items[side].get_mut(index).2 = members.push(handle, 0);
+
+        let mut diffs = VecDeque::new();
+        let visible = self.visible(side, &key);
+        self.visibility[side].insert(index, visible, item, &mut diffs);
+        self.emit(side, &mut diffs, pending);
+        if first {
+            self.refresh(1 - side, &key, pending);
+        }
+    }
+
+    fn remove(&mut self, side: usize, index: usize, pending: &mut VecDeque<VecDiff<T>>) {
+        let (key, _, member) = self.items[side].remove(index);
+        let mut diffs = VecDeque::new();
+        self.visibility[side].remove(index, &mut diffs);
+        self.emit(side, &mut diffs, pending);
+
+        let members = self.members[side].get_mut(&key).expect("removed item");
+        members.remove(members.index_of(member));
+        if members.len() == 0 {
+            self.members[side].remove(&key);
+            self.refresh(1 - side, &key, pending);
+        }
+    }
+
+    // the visible items of the side are replaced, only the other side items with
+    // a key gained or lost by the side are evaluated again
+    fn replace<F>(
+        &mut self,
+        side: usize,
+        values: Vec<T>,
+        key: &mut F,
+        pending: &mut VecDeque<VecDiff<T>>,
+    ) where
+        F: FnMut(&T) -> K,
+    {
+        let removed = self.visibility[side].visible_len();
+        let previous = mem::replace(&mut self.members[side], collect_hash_map(iter::empty()));
+        self.items[side].clear();
+        let mut visible = vec![];
+        let mut shown = vec![];
+        for value in values {
+            let key = key(&value);
+            let is_visible = self.visible(side, &key);
+            if is_visible {
+                shown.push(value.clone());
+            }
+            visible.push(is_visible);
+            let handle = self.items[side].push((key.clone(), value, 0), 0);
+            let members = self.members[side].entry(key).or_default();
+            self.items[side].value_mut(handle).2 = members.push(handle, 0);
+        }
+
+        let mut diffs = VecDeque::new();
+        self.visibility[side].replace_visible(visible, shown, &mut diffs);
+        if self.visibility[1 - side].visible_len() == 0 {
+            // the side is the whole output
+            pending.clear();
+            pending.extend(diffs);
+        } else if let Some(VecDiff::Replace { values }) = diffs.pop_front() {
+            diffs.extend((0..removed).map(|_| VecDiff::RemoveAt { index: 0 }));
+            diffs.extend(
+                values
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, value)| VecDiff::InsertAt { index, value }),
+            );
+            self.emit(side, &mut diffs, pending);
+        }
+
+        let gained = self.members[side]
+            .keys()
+            .filter(|key| !previous.contains_key(*key));
+        let lost = previous
+            .keys()
+            .filter(|key| !self.members[side].contains_key(*key));
+        let changed = gained.chain(lost).cloned().collect::<Vec<_>>();
+        for key in changed {
+            self.refresh(1 - side, &key, pending);
+        }
+    }
+
+    // items of one side with the key are evaluated again
+    fn refresh(&mut self, side: usize, key: &K, pending: &mut VecDeque<VecDiff<T>>) {
+        let visible = self.visible(side, key);
+        let Some(members) = self.members[side].get(key) else {
+            return;
+        };
+        let mut diffs = VecDeque::new();
+        for position in 0..members.len() {
+            let index = self.items[side].index_of(*members.get(position));
+            let item = self.items[side].get(index).1.clone();
+            self.visibility[side].update(index, visible, item, &mut diffs);
+        }
+
+        // items staying visible are unchanged
+        diffs.retain(|diff| !matches!(diff, VecDiff::UpdateAt { .. }));
+        self.emit(side, &mut diffs, pending);
+    }
+}
+
+pin_project! {
+    /// Left items followed by right items, filtered by keys of the other side.
+    /// Duplicate keys within one side are kept.
+    #[derive(Debug)]
+    #[must_use = "SignalVecs do nothing unless polled"]
+    pub struct SetByKey<L, R, F, K>
+    where
+        L: SignalVec,
+    {
+        #[pin]
+        left: Option<L>,
+        #[pin]
+        right: Option<R>,
+        key: F,
+        sides: Sides<K, L::Item>,
+        pending: VecDeque<VecDiff<L::Item>>,
+    }
+}
+
+impl<L, R, F, K> SetByKey<L, R, F, K>
+where
+    L: SignalVec,
+    K: Eq + Hash,
+{
+    pub(crate) fn new(left: L, right: R, key: F, operation: SetOperation) -> Self {
+        Self {
+            left: Some(left),
+            right: Some(right),
+            key,
+            sides: Sides {
+                operation,
+                items: Default::default(),
+                members: [
+                    collect_hash_map(iter::empty()),
+                    collect_hash_map(iter::empty()),
+                ],
+                visibility: Default::default(),
+            },
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl<L, R, F, K> SignalVec for SetByKey<L, R, F, K>
+where
+    L: SignalVec,
+    L::Item: Clone,
+    R: SignalVec<Item = L::Item>,
+    F: FnMut(&L::Item) -> K,
+    K: Eq + Hash + Clone,
+{
+    type Item = L::Item;
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let mut this = self.project();
+
+        // both sides are drained first so that a Replace supersedes earlier diffs
+        let mut done = true;
+        while let Some(poll) = this
+            .right
+            .as_mut()
+            .as_pin_mut()
+            .map(|signal| signal.poll_vec_change(cx))
+        {
+            match poll {
+                Poll::Ready(Some(diff)) => this.sides.apply(RIGHT, diff, this.key, this.pending),
+                Poll::Ready(None) => this.right.set(None),
+                Poll::Pending => {
+                    done = false;
+                    break;
+                }
+            }
+        }
+        while let Some(poll) = this
+            .left
+            .as_mut()
+            .as_pin_mut()
+            .map(|signal| signal.poll_vec_change(cx))
+        {
+            match poll {
+                Poll::Ready(Some(diff)) => this.sides.apply(LEFT, diff, this.key, this.pending),
+                Poll::Ready(None) => this.left.set(None),
+                Poll::Pending => {
+                    done = false;
+                    break;
+                }
+            }
+        }
+
+        match this.pending.pop_front() {
+            Some(diff) => Poll::Ready(Some(diff)),
+            None if done => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
+
+// Items with the items of each key in source order, the first one is visible.
+#[derive(Debug)]
+struct Distinct<K, T> {
+    // items with their key and the handle among the items with the key
+    items: Sequence<(K, T, usize)>,
+    // handles of the items with the key, in the source order
+    members: HashMap<K, Sequence<usize>>,
+    visibility: Visibility,
+}
+
+impl<K, T> Distinct<K, T>
+where
+    K: Eq + Hash + Clone,
+    T: Clone,
+{
+    // handle of the first item with the key
+    fn first(&self, key: &K) -> Option<usize> {
+        self.members.get(key).map(|members| *members.get(0))
+    }
+
+    // position among the items with the key for an item placed at the index
+    fn position_at(&self, key: &K, index: usize) -> usize {
+        let items = &self.items;
+        self.members.get(key).map_or(0, |members| {
+            members.partition_point(|handle| items.index_of(*handle) < index)
+        })
+    }
+
+    fn show(&mut self, handle: usize, visible: bool, pending: &mut VecDeque<VecDiff<T>>) {
+        let index = self.items.index_of(handle);
+        let item = self.items.value(handle).1.clone();
+        self.visibility.update(index, visible, item, pending);
+    }
+
+    fn apply<F>(&mut self, diff: VecDiff<T>, key: &mut F, pending: &mut VecDeque<VecDiff<T>>)
+    where
+        F: FnMut(&T) -> K,
+    {
+        match diff {
+            VecDiff::Replace { values } => {
+                self.items.clear();
+                self.members.clear();
+                let mut visible = vec![];
+                let mut shown = vec![];
+                for value in values {
+                    let key = key(&value);
+                    let first = !self.members.contains_key(&key);
+                    if first {
+                        shown.push(value.clone());
+                    }
+                    visible.push(first);
+                    let handle = self.items.push((key.clone(), value, 0), 0);
+                    let members = self.members.entry(key).or_default();
+                    self.items.value_mut(handle).2 = members.push(handle, 0);
+                }
+                self.visibility.replace_visible(visible, shown, pending);
+            }
+            VecDiff::InsertAt { index, value } => self.insert(index, key(&value), value, pending),
+            VecDiff::UpdateAt { index, value } => {
+                let key = key(&value);
+                if key == self.items.get(index).0 {
+                    self.items.get_mut(index).1 = value.clone();
+                    let visible = self.first(&key) == Some(self.items.handle(index));
+                    self.visibility.update(index, visible, value, pending);
+                } else {
+                    self.remove(index, pending);
+                    self.insert(index, key, value, pending);
+                }
+            }
+            VecDiff::RemoveAt { index } => self.remove(index, pending),
+            VecDiff::Move {
+                old_index,
+                new_index,
+            } => {
+                let (key, _, member) = self.items.get(old_index);
+                let (key, member) = (key.clone(), *member);
+                let previous = self.first(&key).expect("moved item");
+                self.items.move_item(old_index, new_index);
+                self.visibility.move_item(old_index, new_index, pending);
+
+                // the moved item may pass the first item of its key or be passed
+                let members = self.members.get_mut(&key).expect("moved item");
+                let handle = members.remove(members.index_of(member));
+                let position = self.position_at(&key, new_index);
+                let members = self.members.get_mut(&key).expect("moved item");
+                self.items.get_mut(new_index).2 = members.insert(position, handle, 0);
+                let first = *members.get(0);
+                if first != previous {
+                    self.show(previous, false, pending);
+                    self.show(first, true, pending);
+                }
+            }
+            VecDiff::Push { value } => self.insert(self.items.len(), key(&value), value, pending),
+            VecDiff::Pop {} => self.remove(self.items.len() - 1, pending),
+            VecDiff::Clear {} => {
+                self.items.clear();
+                self.members.clear();
+                self.visibility.clear(pending);
+            }
+        }
+    }
+
+    fn insert(&mut self, index: usize, key: K, item: T, pending: &mut VecDeque<VecDiff<T>>) {
+        let previous = self.first(&key);
+        let position = self.position_at(&key, index);
+        let handle = self.items.insert(index, (key.clone(), item.clone(), 0), 0);
+        let members = self.members.entry(key).or_default();
+        self.items.get_mut(index).2 = members.insert(position, handle, 0);
+
+        let first = position == 0;
+        self.visibility.insert(index, first, item, pending);
+        if first && let Some(previous) = previous {
+            self.show(previous, false, pending);
+        }
+    }
+
+    fn remove(&mut self, index: usize, pending: &mut VecDeque<VecDiff<T>>) {
+        let (key, _, member) = self.items.remove(index);
+        self.visibility.remove(index, pending);
+
+        let members = self.members.get_mut(&key).expect("removed item");
+        let position = members.index_of(member);
+        members.remove(position);
+        if members.len() == 0 {
+            self.members.remove(&key);
+        } else if position == 0 {
+            let next = *members.get(0);
+            self.show(next, true, pending);
+        }
+    }
+}
+
+pin_project! {
+    /// First item of each key, a later item with the key is shown when the first
+    /// one is removed.
+    #[derive(Debug)]
+    #[must_use = "SignalVecs do nothing unless polled"]
+    pub struct DistinctByKey<S, F, K>
+    where
+        S: SignalVec,
+    {
+        #[pin]
+        signal: Option<S>,
+        key: F,
+        distinct: Distinct<K, S::Item>,
+        pending: VecDeque<VecDiff<S::Item>>,
+    }
+}
+
+impl<S, F, K> DistinctByKey<S, F, K>
+where
+    S: SignalVec,
+    K: Eq + Hash,
+{
+    pub(crate) fn new(signal: S, key: F) -> Self {
+        Self {
+            signal: Some(signal),
+            key,
+            distinct: Distinct {
+                items: Sequence::default(),
+                members: collect_hash_map(iter::empty()),
+                visibility: Visibility::default(),
+            },
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl<S, F, K> SignalVec for DistinctByKey<S, F, K>
+where
+    S: SignalVec,
+    S::Item: Clone,
+    F: FnMut(&S::Item) -> K,
+    K: Eq + Hash + Clone,
+{
+    type Item = S::Item;
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(diff) = this.pending.pop_front() {
+                return Poll::Ready(Some(diff));
+            }
+
+            match this
+                .signal
+                .as_mut()
+                .as_pin_mut()
+                .map(|signal| signal.poll_vec_change(cx))
+            {
+                Some(Poll::Ready(Some(diff))) => {
+                    this.distinct.apply(diff, this.key, this.pending);
+                }
+                Some(Poll::Ready(None)) => {
+                    this.signal.set(None);
+                    return Poll::Ready(None);
+                }
+                Some(Poll::Pending) => return Poll::Pending,
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::pin::pin;
+
+    use futures_signals::signal_vec::{MutableVec, SignalVecExt, VecDiff};
+
+    use crate::{
+        SignalVecKeyedExt,
+        testing::{poll_signal, poll_vec},
+    };
+
+    #[test]
+    fn distinct_by_key() {
+        let vec = MutableVec::new_with_values(vec![(1, 'a'), (2, 'b'), (1, 'c')]);
+        let mut distinct = pin!(vec.signal_vec().distinct_by_key(|item| item.0));
+        assert_eq!(
+            poll_vec(distinct.as_mut()),
+            vec![VecDiff::Replace {
+                values: vec![(1, 'a'), (2, 'b')]
+            }]
+        );
+
+        vec.lock_mut().remove(0);
+        vec.lock_mut().insert(0, (2, 'd'));
+        vec.lock_mut().push((3, 'e'));
+        assert_eq!(
+            poll_vec(distinct.as_mut()),
+            vec![
+                VecDiff::RemoveAt { index: 0 },
+                VecDiff::InsertAt {
+                    index: 1,
+                    value: (1, 'c')
+                },
+                VecDiff::InsertAt {
+                    index: 0,
+                    value: (2, 'd')
+                },
+                VecDiff::RemoveAt { index: 1 },
+                VecDiff::InsertAt {
+                    index: 2,
+                    value: (3, 'e')
+                }
+            ]
+        );
+
+        // the moved item passes the next item with its key
+        vec.lock_mut().move_from_to(0, 2);
+        assert_eq!(
+            poll_vec(distinct.as_mut()),
+            vec![
+                VecDiff::Move {
+                    old_index: 0,
+                    new_index: 1
+                },
+                VecDiff::RemoveAt { index: 1 },
+                VecDiff::InsertAt {
+                    index: 0,
+                    value: (2, 'b')
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn difference_by_key() {
+        let all = MutableVec::new_with_values(vec![1, 2, 3, 4]);
+        let selected = MutableVec::new_with_values(vec![2]);
+        let mut rest = pin!(
+            all.signal_vec()
+                .difference_by_key(selected.signal_vec(), |item| *item)
+        );
+        assert_eq!(
+            poll_vec(rest.as_mut()),
+            vec![VecDiff::Replace {
+                values: vec![1, 3, 4]
+            }]
+        );
+
+        selected.lock_mut().push(3);
+        selected.lock_mut().remove(0);
+        all.lock_mut().push(5);
+        assert_eq!(
+            poll_vec(rest.as_mut()),
+            vec![
+                VecDiff::RemoveAt { index: 1 },
+                VecDiff::InsertAt { index: 1, value: 2 },
+                VecDiff::InsertAt { index: 3, value: 5 }
+            ]
+        );
+    }
+
+    #[test]
+    fn intersection_and_union_by_key() {
+        let left = MutableVec::new_with_values(vec![1, 2, 3]);
+        let right = MutableVec::new_with_values(vec![4, 3, 1]);
+        let mut intersection = pin!(
+            left.signal_vec()
+                .intersection_by_key(right.signal_vec(), |item| *item)
+                .to_signal_cloned()
+        );
+        let mut union = pin!(
+            left.signal_vec()
+                .union_by_key(right.signal_vec(), |item| *item)
+                .to_signal_cloned()
+        );
+        assert_eq!(poll_signal(intersection.as_mut()).pop(), Some(vec![1, 3]));
+        assert_eq!(poll_signal(union.as_mut()).pop(), Some(vec![1, 2, 3, 4]));
+
+        left.lock_mut().remove(0);
+        right.lock_mut().push(2);
+        right.lock_mut().insert(0, 5);
+        assert_eq!(poll_signal(intersection.as_mut()).pop(), Some(vec![2, 3]));
+        assert_eq!(poll_signal(union.as_mut()).pop(), Some(vec![2, 3, 5, 4, 1]));
+
+        right.lock_mut().clear();
+        assert_eq!(poll_signal(intersection.as_mut()).pop(), Some(vec![]));
+        assert_eq!(poll_signal(union.as_mut()).pop(), Some(vec![2, 3]));
+    }
+
+    #[test]
+    fn other_side_replaced() {
+        let all = MutableVec::new_with_values(vec![1, 2, 3, 4, 5, 6]);
+        let selected = MutableVec::new_with_values(vec![2]);
+        let mut rest = pin!(
+            all.signal_vec()
+                .difference_by_key(selected.signal_vec(), |item| *item)
+        );
+        poll_vec(rest.as_mut());
+
+        // only items whose key is gained or lost by the other side change
+        selected.lock_mut().replace(vec![2, 5]);
+        assert_eq!(
+            poll_vec(rest.as_mut()),
+            vec![VecDiff::RemoveAt { index: 3 }]
+        );
+
+        selected.lock_mut().clear();
+        let mut inserted = poll_vec(rest.as_mut())
+            .into_iter()
+            .map(|diff| match diff {
+                VecDiff::InsertAt { value, .. } => value,
+                diff => panic!("unexpected {diff:?}"),
+            })
+            .collect::<Vec<_>>();
+        inserted.sort();
+        assert_eq!(inserted, vec![2, 5]);
+    }
+
+    #[test]
+    fn set_matches_reference() {
+        let left = MutableVec::new();
+        let right = MutableVec::new();
+        let mut union = pin!(
+            left.signal_vec()
+                .union_by_key(right.signal_vec(), |item: &(u32, u32)| item.0)
+                .to_signal_cloned()
+        );
+        let mut intersection = pin!(
+            left.signal_vec()
+                .intersection_by_key(right.signal_vec(), |item: &(u32, u32)| item.0)
+                .to_signal_cloned()
+        );
+        let mut difference = pin!(
+            left.signal_vec()
+                .difference_by_key(right.signal_vec(), |item: &(u32, u32)| item.0)
+                .to_signal_cloned()
+        );
+        let mut outputs = [vec![], vec![], vec![]];
+        let mut seed = 11u32;
+        let mut random = |bound: usize| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 8) as usize % bound
+        };
+
+        for step in 0..400 {
+            let side = if random(2) == 0 { &left } else { &right };
+            {
+                let mut lock = side.lock_mut();
+                let len = lock.len();
+                let item = (random(6) as u32, step);
+                match random(6) {
+                    0 | 1 => lock.insert(random(len + 1), item),
+                    2 if len > 0 => {
+                        lock.remove(random(len));
+                    }
+                    3 if len > 0 => lock.move_from_to(random(len), random(len)),
+                    4 if len > 0 => lock.set(random(len), item),
+                    5 if random(5) == 0 => {
+                        let values = (0..random(4)).map(|_| (random(6) as u32, step));
+                        lock.replace(values.collect());
+                    }
+                    _ => {}
+                }
+            }
+
+            let (left, right) = (left.lock_ref().to_vec(), right.lock_ref().to_vec());
+            let on =
+                |side: &[(u32, u32)], item: &(u32, u32)| side.iter().any(|other| other.0 == item.0);
+            let mut expected_union = left.clone();
+            expected_union.extend(right.iter().filter(|item| !on(&left, item)));
+            let expected = [
+                expected_union,
+                left.iter()
+                    .filter(|item| on(&right, item))
+                    .copied()
+                    .collect(),
+                left.iter()
+                    .filter(|item| !on(&right, item))
+                    .copied()
+                    .collect(),
+            ];
+            let polled = [
+                poll_signal(union.as_mut()).pop(),
+                poll_signal(intersection.as_mut()).pop(),
+                poll_signal(difference.as_mut()).pop(),
+            ];
+            for ((polled, output), expected) in polled.into_iter().zip(&mut outputs).zip(expected) {
+                if let Some(value) = polled {
+                    *output = value;
+                }
+                assert_eq!(*output, expected, "step {step}");
+            }
+        }
+    }
+
+    #[test]
+    fn distinct_updated() {
+        let vec = MutableVec::new_with_values(vec![(1, 'a'), (1, 'b'), (2, 'c')]);
+        let mut distinct = pin!(vec.signal_vec().distinct_by_key(|item| item.0));
+        poll_vec(distinct.as_mut());
+
+        vec.lock_mut().set(1, (1, 'd'));
+        vec.lock_mut().set(0, (1, 'e'));
+        assert_eq!(
+            poll_vec(distinct.as_mut()),
+            vec![VecDiff::UpdateAt {
+                index: 0,
+                value: (1, 'e')
+            }]
+        );
+
+        vec.lock_mut().set(0, (2, 'e'));
+        assert_eq!(vec.lock_ref().as_slice(), &[(2, 'e'), (1, 'd'), (2, 'c')]);
+        assert_eq!(
+            poll_vec(distinct.as_mut()),
+            vec![
+                VecDiff::RemoveAt { index: 0 },
+                VecDiff::InsertAt {
+                    index: 0,
+                    value: (1, 'd')
+                },
+                VecDiff::InsertAt {
+                    index: 0,
+                    value: (2, 'e')
+                },
+                VecDiff::RemoveAt { index: 2 }
+            ]
+        );
+    }
+}
@@ -0,0 +1,882 @@
+use std::{
+    cmp::Ordering,
+    collections::VecDeque,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_signals::{
+    signal::Signal,
+    signal_vec::{SignalVec, VecDiff},
+};
+use pin_project_lite::pin_project;
+
+use crate::sequence::Sequence;
+
+// ratio of moves to items above which a new comparator is emitted as Replace
+const REPLACE_RATIO: f64 = 0.5;
+
+// Items in source order with sorted permutation of their handles, equal items are
+// kept in source order. Each item keeps the handle of its sorted entry.
+#[derive(Debug)]
+struct Sorted<T> {
+    items: Sequence<(T, usize)>,
+    sorted: Sequence<usize>,
+}
+
+impl<T> Default for Sorted<T> {
+    fn default() -> Self {
+        Self {
+            items: Sequence::default(),
+            sorted: Sequence::default(),
+        }
+    }
+}
+
+impl<T: Clone> Sorted<T> {
+    // sorted position for the item at the index, the item itself must not be sorted
+    fn position<F>(&self, cmp: &mut F, index: usize) -> usize
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let item = &self.items.get(index).0;
+        self.sorted.partition_point(|other| {
+            cmp(&self.items.value(*other).0, item).then(self.items.index_of(*other).cmp(&index))
+                == Ordering::Less
+        })
+    }
+
+    // sorts the item at the index, returns its sorted position
+    fn place<F>(&mut self, cmp: &mut F, index: usize) -> usize
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let position = self.position(cmp, index);
+        let handle = self.items.handle(index);
+        self.items.value_mut(handle).1 = self.sorted.insert(position, handle, 0);
+        position
+    }
+
+    // removes the item at the index from the sorted items, returns its sorted position
+    fn unplace(&mut self, index: usize) -> usize {
+        let position = self.sorted.index_of(self.items.get(index).1);
+        self.sorted.remove(position);
+        position
+    }
+
+    fn values(&self) -> Vec<T> {
+        (0..self.sorted.len())
+            .map(|position| self.items.value(*self.sorted.get(position)).0.clone())
+            .collect()
+    }
+
+    fn insert<F>(&mut self, cmp: &mut F, index: usize, value: T) -> VecDiff<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.items.insert(index, (value.clone(), 0), 0);
+        VecDiff::InsertAt {
+            index: self.place(cmp, index),
+            value,
+        }
+    }
+
+    fn update<F>(&mut self, cmp: &mut F, index: usize, value: T, pending: &mut VecDeque<VecDiff<T>>)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let old_position = self.unplace(index);
+        self.items.get_mut(index).0 = value.clone();
+        let position = self.place(cmp, index);
+        if position == old_position {
+            pending.push_back(VecDiff::UpdateAt {
+                index: position,
+                value,
+            });
+        } else {
+            pending.push_back(VecDiff::RemoveAt {
+                index: old_position,
+            });
+            pending.push_back(VecDiff::InsertAt {
+                index: position,
+                value,
+            });
+        }
+    }
+
+    fn remove(&mut self, index: usize) -> VecDiff<T> {
+        let position = self.unplace(index);
+        self.items.remove(index);
+        VecDiff::RemoveAt { index: position }
+    }
+
+    fn move_item<F>(
+        &mut self,
+        cmp: &mut F,
+        old_index: usize,
+        new_index: usize,
+    ) -> Option<VecDiff<T>>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.items.move_item(old_index, new_index);
+        // source order of equal items may have changed
+        let old_position = self.unplace(new_index);
+        let position = self.place(cmp, new_index);
+        (position != old_position).then_some(VecDiff::Move {
+            old_index: old_position,
+            new_index: position,
+        })
+    }
+
+    fn replace<F>(&mut self, cmp: &mut F, values: Vec<T>) -> VecDiff<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.clear();
+        for value in values {
+            self.items.push((value, 0), 0);
+        }
+        for index in self.order(cmp) {
+            let handle = self.items.handle(index);
+            self.items.value_mut(handle).1 = self.sorted.push(handle, 0);
+        }
+        VecDiff::Replace {
+            values: self.values(),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.items.clear();
+        self.sorted.clear();
+    }
+
+    // source indexes in the order by the comparator
+    fn order<F>(&self, cmp: &mut F) -> Vec<usize>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let items = (0..self.items.len())
+            .map(|index| &self.items.get(index).0)
+            .collect::<Vec<_>>();
+        let mut order = (0..items.len()).collect::<Vec<_>>();
+        order.sort_by(|a, b| cmp(items[*a], items[*b]));
+        order
+    }
+
+    // moves (or Replace) from the current order to the order by the new comparator,
+    // items in the longest increasing subsequence of the current positions stay,
+    // so the number of moves is minimal, O(n log² n)
+    fn resort<F>(&mut self, cmp: &mut F, pending: &mut VecDeque<VecDiff<T>>)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let len = self.items.len();
+        let mut old_positions = vec![0; len];
+        for position in 0..len {
+            old_positions[self.items.index_of(*self.sorted.get(position))] = position;
+        }
+        let order = self.order(cmp);
+        let stays = longest_increasing(
+            &order
+                .iter()
+                .map(|index| old_positions[*index])
+                .collect::<Vec<_>>(),
+        );
+
+        let moves = len - stays.iter().filter(|stays| **stays).count();
+        if moves as f64 > REPLACE_RATIO * len as f64 {
+            self.sorted.clear();
+            for index in order {
+                let handle = self.items.handle(index);
+                self.items.value_mut(handle).1 = self.sorted.push(handle, 0);
+            }
+            pending.push_back(VecDiff::Replace {
+                values: self.values(),
+            });
+            return;
+        }
+
+        // every moved item goes right after its predecessor in the new order, which
+        // is either staying or already moved
+        for (position, index) in order.iter().enumerate() {
+            if stays[position] {
+                continue;
+            }
+            let sorted = self.items.get(*index).1;
+            let old_index = self.sorted.index_of(sorted);
+            let new_index = match position {
+                0 => 0,
+                _ => {
+                    let previous = self.sorted.index_of(self.items.get(order[position - 1]).1);
+                    if previous > old_index {
+                        previous
+                    } else {
+                        previous + 1
+                    }
+                }
+            };
+            self.sorted.move_item(old_index, new_index);
+            pending.push_back(VecDiff::Move {
+                old_index,
+                new_index,
+            });
+        }
+    }
+}
+
+// Marks the items of a longest strictly increasing subsequence, O(n log n).
+fn longest_increasing(values: &[usize]) -> Vec<bool> {
+    // indexes of the smallest tails of the subsequences of every length
+    let mut tails: Vec<usize> = vec![];
+    let mut previous = vec![usize::MAX; values.len()];
+    for (index, value) in values.iter().enumerate() {
+        let length = tails.partition_point(|tail| values[*tail] < *value);
+        if length > 0 {
+            previous[index] = tails[length - 1];
+        }
+        if length == tails.len() {
+            tails.push(index);
+        } else {
+            tails[length] = index;
+        }
+    }
+
+    let mut marks = vec![false; values.len()];
+    let mut index = tails.last().copied().unwrap_or(usize::MAX);
+    while index != usize::MAX {
+        marks[index] = true;
+        index = previous[index];
+    }
+    marks
+}
+
+pin_project! {
+    /// Items sorted by the latest comparator, equal items are kept in source order.
+    /// Nothing is emitted before the first comparator.
+    #[derive(Debug)]
+    #[must_use = "SignalVecs do nothing unless polled"]
+    pub struct SortBySignal<S, C>
+    where
+        S: SignalVec,
+        C: Signal,
+    {
+        #[pin]
+        signal: Option<S>,
+        #[pin]
+        cmp_signal: Option<C>,
+        cmp: Option<C::Item>,
+        sorted: Sorted<S::Item>,
+        pending: VecDeque<VecDiff<S::Item>>,
+    }
+}
+
+impl<S: SignalVec, C: Signal> SortBySignal<S, C> {
+    pub(crate) fn new(signal: S, cmp: C) -> Self {
+        Self {
+            signal: Some(signal),
+            cmp_signal: Some(cmp),
+            cmp: None,
+            sorted: Sorted::default(),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl<S, C, F> SignalVec for SortBySignal<S, C>
+where
+    S: SignalVec,
+    S::Item: Clone,
+    C: Signal<Item = F>,
+    F: FnMut(&S::Item, &S::Item) -> Ordering,
+{
+    type Item = S::Item;
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(diff) = this.pending.pop_front() {
+                return Poll::Ready(Some(diff));
+            }
+
+            let mut cmp_changed = false;
+            while let Some(Poll::Ready(next)) = this
+                .cmp_signal
+                .as_mut()
+                .as_pin_mut()
+                .map(|signal| signal.poll_change(cx))
+            {
+                match next {
+                    Some(cmp) => {
+                        *this.cmp = Some(cmp);
+                        cmp_changed = true;
+                    }
+                    None => this.cmp_signal.set(None),
+                }
+            }
+
+            let Some(cmp) = this.cmp.as_mut() else {
+                return if this.cmp_signal.is_none() {
+                    Poll::Ready(None)
+                } else {
+                    Poll::Pending
+                };
+            };
+
+            if cmp_changed {
+                this.sorted.resort(cmp, this.pending);
+                continue;
+            }
+
+            let diff = match this
+                .signal
+                .as_mut()
+                .as_pin_mut()
+                .map(|signal| signal.poll_vec_change(cx))
+            {
+                Some(Poll::Ready(Some(diff))) => diff,
+                Some(Poll::Ready(None)) => {
+                    this.signal.set(None);
+                    continue;
+                }
+                Some(Poll::Pending) => return Poll::Pending,
+                None if this.cmp_signal.is_none() => return Poll::Ready(None),
+                None => return Poll::Pending,
+            };
+
+            let sorted = &mut *this.sorted;
+            match diff {
+                VecDiff::Replace { values } => {
+                    this.pending.push_back(sorted.replace(cmp, values));
+                }
+                VecDiff::InsertAt { index, value } => {
+                    this.pending.push_back(sorted.insert(cmp, index, value));
+                }
+                VecDiff::UpdateAt { index, value } => {
+                    sorted.update(cmp, index, value, this.pending);
+                }
+                VecDiff::RemoveAt { index } => {
+                    this.pending.push_back(sorted.remove(index));
+                }
+                VecDiff::Move {
+                    old_index,
+                    new_index,
+                } => {
+                    this.pending
+                        .extend(sorted.move_item(cmp, old_index, new_index));
+                }
+                VecDiff::Push { value } => {
+                    let index = sorted.items.len();
+                    this.pending.push_back(sorted.insert(cmp, index, value));
+                }
+                VecDiff::Pop {} => {
+                    let index = sorted.items.len() - 1;
+                    this.pending.push_back(sorted.remove(index));
+                }
+                VecDiff::Clear {} => {
+                    sorted.clear();
+                    this.pending.push_back(VecDiff::Clear {});
+                }
+            }
+        }
+    }
+}
+
+const LEFT: usize = 0;
+const RIGHT: usize = 1;
+
+// Items of both sides, each sorted.
+#[derive(Debug)]
+struct Merged<T> {
+    sides: [Sequence<T>; 2],
+}
+
+impl<T: Clone> Merged<T> {
+    // output index of the item of the side placed at the index
+    fn output_index<F>(&self, cmp: &mut F, side: usize, index: usize, item: &T) -> usize
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        index
+            + self.sides[1 - side].partition_point(|other| match cmp(other, item) {
+                Ordering::Less => true,
+                Ordering::Equal => side == RIGHT,
+                Ordering::Greater => false,
+            })
+    }
+
+    fn values<F>(&self, cmp: &mut F) -> Vec<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let [left, right] = &self.sides;
+        let mut values = Vec::with_capacity(left.len() + right.len());
+        let (mut i, mut j) = (0, 0);
+        while i < left.len() || j < right.len() {
+            if j == right.len()
+                || i < left.len() && cmp(left.get(i), right.get(j)) != Ordering::Greater
+            {
+                values.push(left.get(i).clone());
+                i += 1;
+            } else {
+                values.push(right.get(j).clone());
+                j += 1;
+            }
+        }
+        values
+    }
+
+    fn insert<F>(&mut self, cmp: &mut F, side: usize, index: usize, value: T) -> VecDiff<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let output = self.output_index(cmp, side, index, &value);
+        self.sides[side].insert(index, value.clone(), 0);
+        VecDiff::InsertAt {
+            index: output,
+            value,
+        }
+    }
+
+    fn remove<F>(&mut self, cmp: &mut F, side: usize, index: usize) -> VecDiff<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let output = self.output_index(cmp, side, index, self.sides[side].get(index));
+        self.sides[side].remove(index);
+        VecDiff::RemoveAt { index: output }
+    }
+
+    fn apply<F>(
+        &mut self,
+        cmp: &mut F,
+        side: usize,
+        diff: VecDiff<T>,
+        pending: &mut VecDeque<VecDiff<T>>,
+    ) where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        match diff {
+            VecDiff::Replace { values } => {
+                self.sides[side].clear();
+                for value in values {
+                    self.sides[side].push(value, 0);
+                }
+                self.replace(cmp, pending);
+            }
+            VecDiff::InsertAt { index, value } => {
+                pending.push_back(self.insert(cmp, side, index, value));
+            }
+            VecDiff::UpdateAt { index, value } => {
+                let old_output = self.output_index(cmp, side, index, self.sides[side].get(index));
+                let output = self.output_index(cmp, side, index, &value);
+                *self.sides[side].get_mut(index) = value.clone();
+                if output == old_output {
+                    pending.push_back(VecDiff::UpdateAt {
+                        index: output,
+                        value,
+                    });
+                } else {
+                    pending.push_back(VecDiff::RemoveAt { index: old_output });
+                    pending.push_back(VecDiff::InsertAt {
+                        index: output,
+                        value,
+                    });
+                }
+            }
+            VecDiff::RemoveAt { index } => pending.push_back(self.remove(cmp, side, index)),
+            VecDiff::Move {
+                old_index,
+                new_index,
+            } => {
+                let old_output =
+                    self.output_index(cmp, side, old_index, self.sides[side].get(old_index));
+                self.sides[side].move_item(old_index, new_index);
+                let output =
+                    self.output_index(cmp, side, new_index, self.sides[side].get(new_index));
+                if output != old_output {
+                    pending.push_back(VecDiff::Move {
+                        old_index: old_output,
+                        new_index: output,
+                    });
+                }
+            }
+            VecDiff::Push { value } => {
+                let index = self.sides[side].len();
+                pending.push_back(self.insert(cmp, side, index, value));
+            }
+            VecDiff::Pop {} => {
+                let index = self.sides[side].len() - 1;
+                pending.push_back(self.remove(cmp, side, index));
+            }
+            VecDiff::Clear {} => {
+                self.sides[side].clear();
+                self.replace(cmp, pending);
+            }
+        }
+    }
+
+    // supersedes the diffs not emitted yet
+    fn replace<F>(&mut self, cmp: &mut F, pending: &mut VecDeque<VecDiff<T>>)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        pending.clear();
+        pending.push_back(VecDiff::Replace {
+            values: self.values(cmp),
+        });
+    }
+}
+
+pin_project! {
+    /// Two sorted signal vecs merged, equal items are ordered left first. Each diff
+    /// is O(log n), except for `Replace` and `Clear` of either side emitted as
+    /// `Replace`.
+    #[derive(Debug)]
+    #[must_use = "SignalVecs do nothing unless polled"]
+    pub struct MergeSorted<A, B, F>
+    where
+        A: SignalVec,
+    {
+        #[pin]
+        left: Option<A>,
+        #[pin]
+        right: Option<B>,
+        cmp: F,
+        merged: Merged<A::Item>,
+        pending: VecDeque<VecDiff<A::Item>>,
+    }
+}
+
+impl<A: SignalVec, B, F> MergeSorted<A, B, F> {
+    pub(crate) fn new(left: A, right: B, cmp: F) -> Self {
+        Self {
+            left: Some(left),
+            right: Some(right),
+            cmp,
+            merged: Merged {
+                sides: Default::default(),
+            },
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl<A, B, F> SignalVec for MergeSorted<A, B, F>
+where
+    A: SignalVec,
+    A::Item: Clone,
+    B: SignalVec<Item = A::Item>,
+    F: FnMut(&A::Item, &A::Item) -> Ordering,
+{
+    type Item = A::Item;
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let mut this = self.project();
+
+        let left_done = loop {
+            break match this
+                .left
+                .as_mut()
+                .as_pin_mut()
+                .map(|signal| signal.poll_vec_change(cx))
+            {
+                Some(Poll::Ready(Some(diff))) => {
+                    this.merged.apply(this.cmp, LEFT, diff, this.pending);
+                    continue;
+                }
+                Some(Poll::Ready(None)) => {
+                    this.left.set(None);
+                    true
+                }
+                Some(Poll::Pending) => false,
+                None => true,
+            };
+        };
+
+        let right_done = loop {
+            break match this
+                .right
+                .as_mut()
+                .as_pin_mut()
+                .map(|signal| signal.poll_vec_change(cx))
+            {
+                Some(Poll::Ready(Some(diff))) => {
+                    this.merged.apply(this.cmp, RIGHT, diff, this.pending);
+                    continue;
+                }
+                Some(Poll::Ready(None)) => {
+                    this.right.set(None);
+                    true
+                }
+                Some(Poll::Pending) => false,
+                None => true,
+            };
+        };
+
+        match this.pending.pop_front() {
+            Some(diff) => Poll::Ready(Some(diff)),
+            None if left_done && right_done => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cmp::Ordering, pin::pin};
+
+    use futures_signals::{
+        signal::{Mutable, SignalExt},
+        signal_vec::{MutableVec, VecDiff},
+    };
+
+    use crate::{SignalVecFlattenExt, SignalVecSortExt, testing::poll_vec};
+
+    #[test]
+    fn sort_by_signal() {
+        let vec = MutableVec::new_with_values(vec![3, 1, 4, 2, 5]);
+        let desc = Mutable::new(false);
+        let mut sorted = pin!(
+            vec.signal_vec()
+                .sort_by_signal(desc.signal().map(|desc| {
+                    move |a: &i32, b: &i32| if desc { b.cmp(a) } else { a.cmp(b) }
+                }))
+        );
+        assert_eq!(
+            poll_vec(sorted.as_mut()),
+            vec![VecDiff::Replace {
+                values: vec![1, 2, 3, 4, 5]
+            }]
+        );
+
+        vec.lock_mut().push(0);
+        vec.lock_mut().set(0, 6);
+        assert_eq!(
+            poll_vec(sorted.as_mut()),
+            vec![
+                VecDiff::InsertAt { index: 0, value: 0 },
+                VecDiff::RemoveAt { index: 3 },
+                VecDiff::InsertAt { index: 5, value: 6 }
+            ]
+        );
+
+        // reversing moves most of the items
+        desc.set(true);
+        assert_eq!(
+            poll_vec(sorted.as_mut()),
+            vec![VecDiff::Replace {
+                values: vec![6, 5, 4, 2, 1, 0]
+            }]
+        );
+
+        vec.lock_mut().remove(0);
+        vec.lock_mut().move_from_to(0, 3);
+        assert_eq!(
+            poll_vec(sorted.as_mut()),
+            vec![VecDiff::RemoveAt { index: 0 }]
+        );
+    }
+
+    #[test]
+    fn few_moves() {
+        let vec = MutableVec::new_with_values(vec![(1, 'a'), (2, 'b'), (3, 'a'), (4, 'b')]);
+        let by_letter = Mutable::new(false);
+        let mut sorted = pin!(vec.signal_vec().sort_by_signal(by_letter.signal().map(
+            |by_letter| move |a: &(i32, char), b: &(i32, char)| {
+                if by_letter {
+                    a.1.cmp(&b.1)
+                } else {
+                    Ordering::Equal
+                }
+            }
+        )));
+        poll_vec(sorted.as_mut());
+
+        by_letter.set(true);
+        assert_eq!(
+            poll_vec(sorted.as_mut()),
+            vec![VecDiff::Move {
+                old_index: 2,
+                new_index: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn resort_moves() {
+        let vec = MutableVec::new_with_values((0..20).collect());
+        let first = Mutable::new(5);
+        let mut sorted =
+            pin!(vec.signal_vec().sort_by_signal(first.signal().map(|first| {
+                move |a: &i32, b: &i32| (*a != first, *a).cmp(&(*b != first, *b))
+            })));
+        let mut items = vec![];
+        for diff in poll_vec(sorted.as_mut()) {
+            diff.apply_to_vec(&mut items);
+        }
+
+        first.set(12);
+        let diffs = poll_vec(sorted.as_mut());
+        assert!(
+            diffs
+                .iter()
+                .all(|diff| matches!(diff, VecDiff::Move { .. }))
+        );
+        for diff in diffs {
+            diff.apply_to_vec(&mut items);
+        }
+        let mut expected = (0..20).filter(|item| *item != 12).collect::<Vec<_>>();
+        expected.insert(0, 12);
+        assert_eq!(items, expected);
+    }
+
+    #[test]
+    fn resort_minimal() {
+        let vec = MutableVec::new_with_values((0..10).collect());
+        let last = Mutable::new(None);
+        let mut sorted = pin!(vec.signal_vec().sort_by_signal(last.signal().map(|last| {
+            move |a: &i32, b: &i32| (Some(*a) == last, *a).cmp(&(Some(*b) == last, *b))
+        })));
+        poll_vec(sorted.as_mut());
+
+        last.set(Some(0));
+        assert_eq!(
+            poll_vec(sorted.as_mut()),
+            vec![VecDiff::Move {
+                old_index: 0,
+                new_index: 9
+            }]
+        );
+    }
+
+    #[test]
+    fn sort_matches_reference() {
+        let vec = MutableVec::new();
+        let modulo = Mutable::new(7);
+        let mut sorted = pin!(
+            vec.signal_vec().sort_by_signal(
+                modulo
+                    .signal()
+                    .map(|modulo| { move |a: &u32, b: &u32| (a % modulo).cmp(&(b % modulo)) })
+            )
+        );
+        let mut items = vec![];
+        let mut seed = 3u32;
+        let mut random = |bound: usize| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 8) as usize % bound
+        };
+
+        for step in 0..500 {
+            {
+                let mut lock = vec.lock_mut();
+                let len = lock.len();
+                match random(6) {
+                    0 | 1 => lock.insert(random(len + 1), random(100) as u32),
+                    2 if len > 0 => {
+                        lock.remove(random(len));
+                    }
+                    3 if len > 0 => lock.move_from_to(random(len), random(len)),
+                    4 if len > 0 => lock.set(random(len), random(100) as u32),
+                    5 => modulo.set(random(10) as u32 + 1),
+                    _ => {}
+                }
+            }
+            for diff in poll_vec(sorted.as_mut()) {
+                diff.apply_to_vec(&mut items);
+            }
+
+            let modulo = modulo.get();
+            let mut expected = vec.lock_ref().to_vec();
+            expected.sort_by_key(|item| item % modulo);
+            assert_eq!(items, expected, "step {step}");
+        }
+    }
+
+    #[test]
+    fn merge_sorted_by() {
+        let a = MutableVec::new_with_values(vec![1, 4, 6]);
+        let b = MutableVec::new_with_values(vec![2, 4, 5]);
+        let mut merged = pin!(
+            a.signal_vec()
+                .merge_sorted_by(b.signal_vec(), |a, b| a.cmp(b))
+        );
+        assert_eq!(
+            poll_vec(merged.as_mut()),
+            vec![VecDiff::Replace {
+                values: vec![1, 2, 4, 4, 5, 6]
+            }]
+        );
+
+        b.lock_mut().push(7);
+        a.lock_mut().insert(0, 0);
+        assert_eq!(
+            poll_vec(merged.as_mut()),
+            vec![
+                VecDiff::InsertAt { index: 0, value: 0 },
+                VecDiff::InsertAt { index: 7, value: 7 }
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_sorted_matches_reference() {
+        let sides = [MutableVec::new(), MutableVec::new()];
+        let mut merged = pin!(
+            sides[0]
+                .signal_vec()
+                .merge_sorted_by(sides[1].signal_vec(), |a: &(u32, usize), b| a.0.cmp(&b.0))
+        );
+        let mut items = vec![];
+        let mut seed = 3u32;
+        let mut random = |bound: usize| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 8) as usize % bound
+        };
+
+        for _ in 0..300 {
+            let side = random(2);
+            let mut vec = sides[side].lock_mut();
+            let len = vec.len();
+            match random(5) {
+                0 | 1 => {
+                    let value = (random(20) as u32, side);
+                    let index = vec.partition_point(|item| item.0 <= value.0);
+                    vec.insert(index, value);
+                }
+                2 if len > 0 => {
+                    vec.remove(random(len));
+                }
+                3 if len > 0 => {
+                    // updated within its neighbours
+                    let index = random(len);
+                    let low = if index > 0 { vec[index - 1].0 } else { 0 };
+                    let high = vec.get(index + 1).map_or(20, |item| item.0);
+                    vec.set(
+                        index,
+                        (low + random((high - low) as usize + 1) as u32, side),
+                    );
+                }
+                4 if random(10) == 0 => vec.clear(),
+                _ => {}
+            }
+            drop(vec);
+
+            for diff in poll_vec(merged.as_mut()) {
+                diff.apply_to_vec(&mut items);
+            }
+            let mut expected = sides[0].lock_ref().to_vec();
+            expected.extend(sides[1].lock_ref().iter().copied());
+            expected.sort_by_key(|item| item.0);
+            assert_eq!(items, expected);
+        }
+    }
+}
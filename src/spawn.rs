@@ -2,6 +2,8 @@ use std::future::Future;
 
 use futures_signals::signal_vec::{MutableVec, MutableVecLockMut, VecDiff};
 
+use crate::version::bump_version;
+
 pub trait SignalSpawn<A> {
     #[cfg(feature = "spawn")]
     fn spawn<F>(self, f: F)
@@ -43,6 +45,7 @@ pub trait SignalVecSpawn<A> {
     {
         self.spawn(move |diff| {
             MutableVecLockMut::apply_vec_diff(&mut target.lock_mut(), diff);
+            bump_version();
         });
     }
 
@@ -54,6 +57,7 @@ pub trait SignalVecSpawn<A> {
     {
         self.spawn(move |diff| {
             MutableVecLockMut::apply_vec_diff(&mut target.lock_mut(), diff);
+            bump_version();
         });
     }
 
@@ -70,6 +74,7 @@ pub trait SignalVecSpawn<A> {
     {
         self.spawn_local(move |diff| {
             MutableVecLockMut::apply_vec_diff(&mut target.lock_mut(), diff);
+            bump_version();
         });
     }
 
@@ -81,6 +86,7 @@ pub trait SignalVecSpawn<A> {
     {
         self.spawn_local(move |diff| {
             MutableVecLockMut::apply_vec_diff(&mut target.lock_mut(), diff);
+            bump_version();
         });
     }
 }
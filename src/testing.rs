@@ -0,0 +1,56 @@
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use futures_signals::{
+    signal::Signal,
+    signal_map::{MapDiff, SignalMap},
+    signal_vec::{SignalVec, VecDiff},
+};
+
+pub(crate) fn poll_vec<S: SignalVec>(mut signal: Pin<&mut S>) -> Vec<VecDiff<S::Item>> {
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut diffs = vec![];
+    while let Poll::Ready(Some(diff)) = signal.as_mut().poll_vec_change(&mut cx) {
+        diffs.push(diff);
+    }
+    diffs
+}
+
+pub(crate) fn poll_signal<S: Signal>(mut signal: Pin<&mut S>) -> Vec<S::Item> {
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut values = vec![];
+    while let Poll::Ready(Some(value)) = signal.as_mut().poll_change(&mut cx) {
+        values.push(value);
+    }
+    values
+}
+
+pub(crate) fn poll_map<M: SignalMap>(mut map: Pin<&mut M>) -> Vec<MapDiff<M::Key, M::Value>> {
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut diffs = vec![];
+    while let Poll::Ready(Some(diff)) = map.as_mut().poll_map_change(&mut cx) {
+        diffs.push(diff);
+    }
+    diffs
+}
+
+thread_local! {
+    static TASKS: RefCell<Vec<Pin<Box<dyn Future<Output = ()>>>>> = const { RefCell::new(vec![]) };
+}
+
+pub(crate) fn spawn(task: impl Future<Output = ()> + 'static) {
+    TASKS.with_borrow_mut(|tasks| tasks.push(Box::pin(task)));
+}
+
+// Polls the spawned tasks once, returns the number of tasks still pending.
+pub(crate) fn run_tasks() -> usize {
+    let mut cx = Context::from_waker(Waker::noop());
+    TASKS.with_borrow_mut(|tasks| {
+        tasks.retain_mut(|task| task.as_mut().poll(&mut cx).is_pending());
+        tasks.len()
+    })
+}
@@ -0,0 +1,391 @@
+use std::ops::Deref;
+
+use futures_signals::signal_vec::{MutableVecLockMut, VecDiff};
+
+use crate::version::bump_version;
+
+const DEFAULT_REPLACE_RATIO: f64 = 0.5;
+
+/// Records changes done to a `MutableVec` and applies them at once, when the
+/// transaction is finished. Recorded changes are coalesced, e.g. update of an
+/// inserted item becomes the insert, removal of an inserted item cancels both.
+/// If the number of remaining changes exceeds `replace ratio * len`, single
+/// `VecDiff::Replace` is emitted instead. With the default ratio of 0.5 this
+/// includes a single push into an empty vec, `f64::INFINITY` keeps the
+/// individual changes always.
+pub struct Transaction<A> {
+    values: Vec<A>,
+    ops: Vec<VecDiff<A>>,
+    replaced: bool,
+    replace_ratio: f64,
+}
+
+impl<A> Transaction<A> {
+    pub(crate) fn new(values: Vec<A>) -> Self {
+        Self {
+            values,
+            ops: vec![],
+            replaced: false,
+            replace_ratio: DEFAULT_REPLACE_RATIO,
+        }
+    }
+
+    pub fn set_replace_ratio(&mut self, ratio: f64) {
+        self.replace_ratio = ratio;
+    }
+
+    pub fn push(&mut self, value: A)
+    where
+        A: Clone,
+    {
+        let index = self.values.len();
+        self.insert(index, value);
+    }
+
+    pub fn insert(&mut self, index: usize, value: A)
+    where
+        A: Clone,
+    {
+        self.values.insert(index, value.clone());
+        if !self.replaced {
+            self.ops.push(VecDiff::InsertAt { index, value });
+        }
+    }
+
+    pub fn set(&mut self, index: usize, value: A)
+    where
+        A: Clone,
+    {
+        self.values[index] = value.clone();
+        if !self.replaced {
+            self.record_update(index, value);
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<A> {
+        if self.values.is_empty() {
+            None
+        } else {
+            Some(self.remove(self.values.len() - 1))
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> A {
+        let value = self.values.remove(index);
+        if !self.replaced {
+            self.record_removal(index);
+        }
+        value
+    }
+
+    pub fn move_from_to(&mut self, old_index: usize, new_index: usize) {
+        if old_index != new_index {
+            let value = self.values.remove(old_index);
+            self.values.insert(new_index, value);
+            if !self.replaced {
+                self.ops.push(VecDiff::Move {
+                    old_index,
+                    new_index,
+                });
+            }
+        }
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&A) -> bool,
+    {
+        // going backwards keeps indexes of items yet to be checked valid
+        for index in (0..self.values.len()).rev() {
+            if !f(&self.values[index]) {
+                self.remove(index);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.replace(vec![]);
+    }
+
+    pub fn replace(&mut self, values: Vec<A>) {
+        self.values = values;
+        self.ops.clear();
+        self.replaced = true;
+    }
+
+    fn record_update(&mut self, updated: usize, value: A) {
+        let mut index = updated;
+        for op in self.ops.iter_mut().rev() {
+            match op {
+                VecDiff::InsertAt {
+                    index: op_index,
+                    value: op_value,
+                }
+                | VecDiff::UpdateAt {
+                    index: op_index,
+                    value: op_value,
+                } if *op_index == index => {
+                    *op_value = value;
+                    return;
+                }
+                VecDiff::InsertAt {
+                    index: op_index, ..
+                } => {
+                    if *op_index < index {
+                        index -= 1;
+                    }
+                }
+                VecDiff::UpdateAt { .. } => {}
+                VecDiff::RemoveAt { index: op_index } => {
+                    if *op_index <= index {
+                        index += 1;
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        self.ops.push(VecDiff::UpdateAt {
+            index: updated,
+            value,
+        });
+    }
+
+    // Walking back through the log, `positions[n]` keeps index of the removed
+    // item in state after op `n`. If the item was inserted in this transaction,
+    // the insert is dropped together with all updates of the item, and indexes
+    // of ops done in between are shifted as if the item never existed.
+    fn record_removal(&mut self, removed: usize) {
+        let mut index = removed;
+        let mut positions = vec![0; self.ops.len()];
+        let mut updates = vec![];
+        let mut inserted = None;
+
+        for (op_index, op) in self.ops.iter().enumerate().rev() {
+            positions[op_index] = index;
+            match op {
+                VecDiff::InsertAt { index: at, .. } if *at == index => {
+                    inserted = Some(op_index);
+                    break;
+                }
+                VecDiff::InsertAt { index: at, .. } => {
+                    if *at < index {
+                        index -= 1;
+                    }
+                }
+                VecDiff::UpdateAt { index: at, .. } => {
+                    if *at == index {
+                        updates.push(op_index);
+                    }
+                }
+                VecDiff::RemoveAt { index: at } => {
+                    if *at <= index {
+                        index += 1;
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        match inserted {
+            Some(inserted) => {
+                for op_index in inserted + 1..self.ops.len() {
+                    let position = positions[op_index - 1];
+                    match &mut self.ops[op_index] {
+                        VecDiff::InsertAt { index, .. }
+                        | VecDiff::UpdateAt { index, .. }
+                        | VecDiff::RemoveAt { index } => {
+                            if *index > position {
+                                *index -= 1;
+                            }
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+
+                // updates are collected with descending op indexes
+                for op_index in updates {
+                    self.ops.remove(op_index);
+                }
+                self.ops.remove(inserted);
+            }
+            None => {
+                for op_index in updates {
+                    self.ops.remove(op_index);
+                }
+                self.ops.push(VecDiff::RemoveAt { index: removed });
+            }
+        }
+    }
+
+    /// When `inverse` is provided, diffs reverting the applied changes are pushed
+    /// into it, in the order in which they were applied.
+    pub(crate) fn commit(
+        self,
+        lock: &mut MutableVecLockMut<A>,
+        mut inverse: Option<&mut Vec<VecDiff<A>>>,
+    ) where
+        A: Clone,
+    {
+        let len = lock.len().max(self.values.len());
+        let ops = if self.replaced || self.ops.len() as f64 > self.replace_ratio * len as f64 {
+            vec![VecDiff::Replace {
+                values: self.values,
+            }]
+        } else {
+            self.ops
+        };
+
+        if !ops.is_empty() {
+            bump_version();
+        }
+        for op in ops {
+            match inverse.as_mut() {
+                Some(inverse) => inverse.push(apply_diff_inverse(lock, op)),
+                None => apply_diff(lock, op),
+            }
+        }
+    }
+}
+
+fn apply_diff<A: Clone>(lock: &mut MutableVecLockMut<A>, diff: VecDiff<A>) {
+    match diff {
+        VecDiff::Replace { values } if values.is_empty() => lock.clear(),
+        diff => MutableVecLockMut::apply_vec_diff(lock, diff),
+    }
+}
+
+/// Applies the diff and returns the diff reverting it.
+pub(crate) fn apply_diff_inverse<A: Clone>(
+    lock: &mut MutableVecLockMut<A>,
+    diff: VecDiff<A>,
+) -> VecDiff<A> {
+    match diff {
+        VecDiff::Replace { values } => {
+            let old = lock.to_vec();
+            apply_diff(lock, VecDiff::Replace { values });
+            VecDiff::Replace { values: old }
+        }
+        VecDiff::InsertAt { index, value } => {
+            lock.insert_cloned(index, value);
+            VecDiff::RemoveAt { index }
+        }
+        VecDiff::UpdateAt { index, value } => {
+            let old = lock[index].clone();
+            lock.set_cloned(index, value);
+            VecDiff::UpdateAt { index, value: old }
+        }
+        VecDiff::RemoveAt { index } => VecDiff::InsertAt {
+            index,
+            value: lock.remove(index),
+        },
+        VecDiff::Move {
+            old_index,
+            new_index,
+        } => {
+            lock.move_from_to(old_index, new_index);
+            VecDiff::Move {
+                old_index: new_index,
+                new_index: old_index,
+            }
+        }
+        VecDiff::Push { value } => {
+            lock.push_cloned(value);
+            VecDiff::Pop {}
+        }
+        VecDiff::Pop {} => VecDiff::Push {
+            value: lock.pop().unwrap(),
+        },
+        VecDiff::Clear {} => {
+            let old = lock.to_vec();
+            lock.clear();
+            VecDiff::Replace { values: old }
+        }
+    }
+}
+
+impl<A> Deref for Transaction<A> {
+    type Target = [A];
+
+    fn deref(&self) -> &[A] {
+        &self.values
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::pin::pin;
+
+    use futures_signals::signal_vec::{MutableVec, VecDiff};
+
+    use crate::{MutableVecExt, testing::poll_vec};
+
+    #[test]
+    fn coalesce() {
+        let vec = MutableVec::new_with_values(vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        let mut signal = pin!(vec.signal_vec());
+        poll_vec(signal.as_mut());
+
+        vec.transaction(|tx| {
+            tx.push(9);
+            tx.set(8, 10);
+            tx.insert(0, 0);
+            tx.set(0, 1);
+            tx.remove(0);
+            tx.remove(2);
+            tx.set(1, 20);
+        });
+
+        assert_eq!(
+            poll_vec(signal.as_mut()),
+            vec![
+                VecDiff::Push { value: 10 },
+                VecDiff::RemoveAt { index: 2 },
+                VecDiff::UpdateAt {
+                    index: 1,
+                    value: 20
+                },
+            ]
+        );
+        assert_eq!(vec.lock_ref().as_slice(), &[1, 20, 4, 5, 6, 7, 8, 10]);
+    }
+
+    #[test]
+    fn push_into_empty() {
+        let vec = MutableVec::new();
+        let mut signal = pin!(vec.signal_vec());
+        poll_vec(signal.as_mut());
+
+        // one change on one item is over the default ratio
+        vec.transaction(|tx| tx.push(1));
+        assert_eq!(
+            poll_vec(signal.as_mut()),
+            vec![VecDiff::Replace { values: vec![1] }]
+        );
+
+        vec.transaction(|tx| {
+            tx.set_replace_ratio(f64::INFINITY);
+            tx.push(2);
+        });
+        assert_eq!(poll_vec(signal.as_mut()), vec![VecDiff::Push { value: 2 }]);
+    }
+
+    #[test]
+    fn replace_over_ratio() {
+        let vec = MutableVec::new_with_values(vec![1, 2, 3]);
+        let mut signal = pin!(vec.signal_vec());
+        poll_vec(signal.as_mut());
+
+        vec.transaction(|tx| {
+            tx.set(0, 10);
+            tx.remove(1);
+        });
+
+        assert_eq!(
+            poll_vec(signal.as_mut()),
+            vec![VecDiff::Replace {
+                values: vec![10, 3]
+            }]
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use std::sync::{
+    LazyLock,
+    atomic::{AtomicU64, Ordering},
+};
+
+use futures_signals::signal::{Mutable, MutableSignal};
+
+// Version of all changes made through the crate, the signal follows it.
+static VERSION: AtomicU64 = AtomicU64::new(0);
+static VERSION_SIGNAL: LazyLock<Mutable<u64>> = LazyLock::new(|| Mutable::new(0));
+
+// Called by every mutation path of the crate (ext traits, entries, transactions).
+pub(crate) fn bump_version() {
+    let version = VERSION.fetch_add(1, Ordering::AcqRel) + 1;
+    // concurrent bumps may arrive out of order, the signal never goes back
+    let mut lock = VERSION_SIGNAL.lock_mut();
+    if *lock < version {
+        *lock = version;
+    }
+}
+
+pub(crate) fn version() -> u64 {
+    VERSION.load(Ordering::Acquire)
+}
+
+pub(crate) fn signal_version() -> MutableSignal<u64> {
+    VERSION_SIGNAL.signal()
+}
+
+#[cfg(test)]
+mod test {
+    use std::pin::pin;
+
+    use futures_signals::{signal::Mutable, signal_vec::MutableVec};
+
+    use crate::{MutableExt, MutableVecEntry, MutableVecExt, testing::poll_signal};
+
+    #[test]
+    fn vec_version() {
+        let vec = MutableVec::new_with_values(vec![1, 2]);
+        let mut signal = pin!(vec.signal_version());
+        let initial = poll_signal(signal.as_mut());
+        assert_eq!(initial.len(), 1);
+
+        let version = vec.version();
+        assert!(vec.find_set(|item| *item == 1, 10));
+        let found = vec.version();
+        assert!(found > version);
+
+        vec.entry(|item| *item == 5).or_insert(5);
+        assert!(vec.synchronize(|item| *item, [10, 2, 3]));
+        assert!(vec.version() > found);
+        assert_eq!(vec.lock_ref().as_slice(), &[10, 2, 3]);
+
+        // the signal follows the version
+        let versions = poll_signal(signal.as_mut());
+        assert!(versions.len() == 1 && versions[0] > initial[0]);
+    }
+
+    #[test]
+    fn mutable_version() {
+        let value = Mutable::new(0);
+        let version = value.version();
+        value.inspect_mut(|value| *value = 1);
+        assert!(value.version() > version);
+
+        let version = value.version();
+        assert_eq!(value.take(), 1);
+        assert!(value.version() > version);
+    }
+}
@@ -0,0 +1,454 @@
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_signals::{
+    signal::{Mutable, Signal},
+    signal_vec::{SignalVec, VecDiff},
+};
+use pin_project_lite::pin_project;
+
+// Source items with the window `[offset, offset + len)` over them.
+#[derive(Debug)]
+struct Slice<T> {
+    items: Vec<T>,
+    offset: usize,
+    len: usize,
+}
+
+impl<T: Clone> Slice<T> {
+    // window clamped to the items
+    fn range(&self) -> (usize, usize) {
+        let total = self.items.len();
+        (
+            self.offset.min(total),
+            self.offset.saturating_add(self.len).min(total),
+        )
+    }
+
+    fn values(&self) -> Vec<T> {
+        let (start, end) = self.range();
+        self.items[start..end].to_vec()
+    }
+
+    fn insert(&mut self, index: usize, value: T, pending: &mut VecDeque<VecDiff<T>>) {
+        let (start, end) = self.range();
+        self.items.insert(index, value);
+
+        let window_end = self.offset.saturating_add(self.len);
+        if self.len == 0 || index >= window_end {
+            return;
+        }
+
+        // the last item is shifted out of the full window
+        if end == window_end {
+            pending.push_back(VecDiff::RemoveAt {
+                index: end - start - 1,
+            });
+        }
+        if index < self.offset {
+            // the item before the window is shifted in
+            if self.offset < self.items.len() {
+                pending.push_back(VecDiff::InsertAt {
+                    index: 0,
+                    value: self.items[self.offset].clone(),
+                });
+            }
+        } else {
+            pending.push_back(VecDiff::InsertAt {
+                index: index - self.offset,
+                value: self.items[index].clone(),
+            });
+        }
+    }
+
+    fn update(&mut self, index: usize, value: T, pending: &mut VecDeque<VecDiff<T>>) {
+        let (start, end) = self.range();
+        if start <= index && index < end {
+            pending.push_back(VecDiff::UpdateAt {
+                index: index - start,
+                value: value.clone(),
+            });
+        }
+        self.items[index] = value;
+    }
+
+    fn remove(&mut self, index: usize, pending: &mut VecDeque<VecDiff<T>>) {
+        let (start, end) = self.range();
+        let total = self.items.len();
+        self.items.remove(index);
+        if index >= end || start == end {
+            return;
+        }
+
+        pending.push_back(VecDiff::RemoveAt {
+            index: index.saturating_sub(start),
+        });
+        // the item after the window is shifted in
+        if end < total {
+            pending.push_back(VecDiff::InsertAt {
+                index: end - start - 1,
+                value: self.items[end - 1].clone(),
+            });
+        }
+    }
+
+    fn move_item(
+        &mut self,
+        old_index: usize,
+        new_index: usize,
+        pending: &mut VecDeque<VecDiff<T>>,
+    ) {
+        let (start, end) = self.range();
+        if (start..end).contains(&old_index) && (start..end).contains(&new_index) {
+            let item = self.items.remove(old_index);
+            self.items.insert(new_index, item);
+            pending.push_back(VecDiff::Move {
+                old_index: old_index - start,
+                new_index: new_index - start,
+            });
+        } else {
+            let item = self.items[old_index].clone();
+            self.remove(old_index, pending);
+            self.insert(new_index, item, pending);
+        }
+    }
+
+    fn replace(&mut self, values: Vec<T>, pending: &mut VecDeque<VecDiff<T>>) {
+        self.items = values;
+        pending.push_back(VecDiff::Replace {
+            values: self.values(),
+        });
+    }
+
+    fn clear(&mut self, pending: &mut VecDeque<VecDiff<T>>) {
+        self.items.clear();
+        pending.push_back(VecDiff::Clear {});
+    }
+
+    // items shifted in and out at the edges, or Replace if that is shorter
+    fn set_range(&mut self, offset: usize, len: usize, pending: &mut VecDeque<VecDiff<T>>) {
+        let (old_start, old_end) = self.range();
+        self.offset = offset;
+        self.len = len;
+        let (start, end) = self.range();
+        if (old_start, old_end) == (start, end) {
+            return;
+        }
+
+        let changes = start.abs_diff(old_start) + end.abs_diff(old_end);
+        if end <= old_start || old_end <= start || changes > end - start {
+            pending.push_back(VecDiff::Replace {
+                values: self.values(),
+            });
+            return;
+        }
+
+        for _ in old_start..start {
+            pending.push_back(VecDiff::RemoveAt { index: 0 });
+        }
+        let mut size = old_end - old_start.max(start);
+        for _ in end..old_end {
+            size -= 1;
+            pending.push_back(VecDiff::RemoveAt { index: size });
+        }
+        for index in (start..old_start).rev() {
+            pending.push_back(VecDiff::InsertAt {
+                index: 0,
+                value: self.items[index].clone(),
+            });
+        }
+        for index in old_end..end {
+            pending.push_back(VecDiff::Push {
+                value: self.items[index].clone(),
+            });
+        }
+    }
+}
+
+pin_project! {
+    /// Items `[offset, offset + len)` of the source, nothing is emitted before both
+    /// the offset and the len are known.
+    #[derive(Debug)]
+    #[must_use = "SignalVecs do nothing unless polled"]
+    pub struct Window<S, O, L>
+    where
+        S: SignalVec,
+    {
+        #[pin]
+        signal: Option<S>,
+        #[pin]
+        offset_signal: Option<O>,
+        #[pin]
+        len_signal: Option<L>,
+        offset: Option<usize>,
+        len: Option<usize>,
+        slice: Slice<S::Item>,
+        total: Mutable<usize>,
+        pending: VecDeque<VecDiff<S::Item>>,
+    }
+}
+
+impl<S: SignalVec, O, L> Window<S, O, L> {
+    pub(crate) fn new(signal: S, offset: O, len: L) -> Self {
+        Self {
+            signal: Some(signal),
+            offset_signal: Some(offset),
+            len_signal: Some(len),
+            offset: None,
+            len: None,
+            slice: Slice {
+                items: vec![],
+                offset: 0,
+                len: 0,
+            },
+            total: Mutable::new(0),
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Length of the source, updated as the window is polled.
+    pub fn signal_total_len(&self) -> impl Signal<Item = usize> + use<S, O, L> {
+        self.total.signal()
+    }
+}
+
+fn poll_latest<S: Signal>(
+    mut signal: Pin<&mut Option<S>>,
+    value: &mut Option<S::Item>,
+    cx: &mut Context,
+) {
+    while let Some(Poll::Ready(next)) = signal
+        .as_mut()
+        .as_pin_mut()
+        .map(|signal| signal.poll_change(cx))
+    {
+        match next {
+            Some(next) => *value = Some(next),
+            None => signal.set(None),
+        }
+    }
+}
+
+impl<S, O, L> SignalVec for Window<S, O, L>
+where
+    S: SignalVec,
+    S::Item: Clone,
+    O: Signal<Item = usize>,
+    L: Signal<Item = usize>,
+{
+    type Item = S::Item;
+
+    fn poll_vec_change(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Option<VecDiff<Self::Item>>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(diff) = this.pending.pop_front() {
+                return Poll::Ready(Some(diff));
+            }
+
+            poll_latest(this.offset_signal.as_mut(), this.offset, cx);
+            poll_latest(this.len_signal.as_mut(), this.len, cx);
+            let signals_done = this.offset_signal.is_none() && this.len_signal.is_none();
+
+            let (Some(offset), Some(len)) = (*this.offset, *this.len) else {
+                return if signals_done {
+                    Poll::Ready(None)
+                } else {
+                    Poll::Pending
+                };
+            };
+
+            if (offset, len) != (this.slice.offset, this.slice.len) {
+                this.slice.set_range(offset, len, this.pending);
+                continue;
+            }
+
+            let diff = match this
+                .signal
+                .as_mut()
+                .as_pin_mut()
+                .map(|signal| signal.poll_vec_change(cx))
+            {
+                Some(Poll::Ready(Some(diff))) => diff,
+                Some(Poll::Ready(None)) => {
+                    this.signal.set(None);
+                    continue;
+                }
+                Some(Poll::Pending) => return Poll::Pending,
+                None if signals_done => return Poll::Ready(None),
+                None => return Poll::Pending,
+            };
+
+            let slice = &mut *this.slice;
+            match diff {
+                VecDiff::Replace { values } => slice.replace(values, this.pending),
+                VecDiff::InsertAt { index, value } => slice.insert(index, value, this.pending),
+                VecDiff::UpdateAt { index, value } => slice.update(index, value, this.pending),
+                VecDiff::RemoveAt { index } => slice.remove(index, this.pending),
+                VecDiff::Move {
+                    old_index,
+                    new_index,
+                } => slice.move_item(old_index, new_index, this.pending),
+                VecDiff::Push { value } => slice.insert(slice.items.len(), value, this.pending),
+                VecDiff::Pop {} => slice.remove(slice.items.len() - 1, this.pending),
+                VecDiff::Clear {} => slice.clear(this.pending),
+            }
+            this.total.set_neq(slice.items.len());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::pin::pin;
+
+    use futures_signals::{
+        signal::{Mutable, always},
+        signal_vec::{MutableVec, SignalVecExt, VecDiff},
+    };
+
+    use crate::{
+        SignalVecWindowExt,
+        testing::{poll_signal, poll_vec},
+    };
+
+    // window contents after each poll, checked against slicing the source
+    #[test]
+    fn window() {
+        let vec = MutableVec::new_with_values((0..10).collect());
+        let offset = Mutable::new(2);
+        let len = Mutable::new(3);
+        let window = vec.signal_vec().window(offset.signal(), len.signal());
+        let mut total = pin!(window.signal_total_len());
+        let mut values = pin!(window.to_signal_cloned());
+        let mut check = |offset: usize, len: usize| {
+            let expected = vec
+                .lock_ref()
+                .iter()
+                .skip(offset)
+                .take(len)
+                .copied()
+                .collect();
+            assert_eq!(poll_signal(values.as_mut()).pop(), Some(expected));
+        };
+        check(2, 3);
+        assert_eq!(poll_signal(total.as_mut()), vec![10]);
+
+        vec.lock_mut().insert(0, 10);
+        check(2, 3);
+        vec.lock_mut().insert(3, 11);
+        check(2, 3);
+        vec.lock_mut().remove(1);
+        check(2, 3);
+        vec.lock_mut().remove(3);
+        check(2, 3);
+        vec.lock_mut().set(4, 12);
+        check(2, 3);
+        vec.lock_mut().move_from_to(0, 3);
+        check(2, 3);
+        vec.lock_mut().move_from_to(3, 4);
+        check(2, 3);
+
+        offset.set(3);
+        check(3, 3);
+        len.set(5);
+        check(3, 5);
+        offset.set(8);
+        check(8, 5);
+        vec.lock_mut().truncate(9);
+        check(8, 5);
+        vec.lock_mut().insert(0, 13);
+        check(8, 5);
+        assert_eq!(poll_signal(total.as_mut()), vec![10]);
+    }
+
+    #[test]
+    fn edges() {
+        let vec = MutableVec::new_with_values(vec![1, 2, 3]);
+        let mut window = pin!(vec.signal_vec().window(always(1), always(2)));
+        assert_eq!(
+            poll_vec(window.as_mut()),
+            vec![VecDiff::Replace { values: vec![2, 3] }]
+        );
+
+        vec.lock_mut().insert(0, 0);
+        assert_eq!(
+            poll_vec(window.as_mut()),
+            vec![
+                VecDiff::RemoveAt { index: 1 },
+                VecDiff::InsertAt { index: 0, value: 1 }
+            ]
+        );
+
+        vec.lock_mut().remove(1);
+        assert_eq!(
+            poll_vec(window.as_mut()),
+            vec![
+                VecDiff::RemoveAt { index: 0 },
+                VecDiff::InsertAt { index: 1, value: 3 }
+            ]
+        );
+    }
+
+    #[test]
+    fn take_skip() {
+        let vec = MutableVec::new_with_values(vec![1, 2, 3, 4]);
+        let n = Mutable::new(2);
+        let mut take = pin!(vec.signal_vec().take(2));
+        let mut take_signal = pin!(vec.signal_vec().take_signal(n.signal()));
+        let mut skip = pin!(vec.signal_vec().skip(3));
+        assert_eq!(
+            poll_vec(take.as_mut()),
+            vec![VecDiff::Replace { values: vec![1, 2] }]
+        );
+        poll_vec(take_signal.as_mut());
+        assert_eq!(
+            poll_vec(skip.as_mut()),
+            vec![VecDiff::Replace { values: vec![4] }]
+        );
+
+        vec.lock_mut().remove(0);
+        assert_eq!(
+            poll_vec(take.as_mut()),
+            vec![
+                VecDiff::RemoveAt { index: 0 },
+                VecDiff::InsertAt { index: 1, value: 3 }
+            ]
+        );
+        assert_eq!(
+            poll_vec(skip.as_mut()),
+            vec![VecDiff::RemoveAt { index: 0 }]
+        );
+
+        vec.lock_mut().insert(0, 0);
+        assert_eq!(
+            poll_vec(take.as_mut()),
+            vec![
+                VecDiff::RemoveAt { index: 1 },
+                VecDiff::InsertAt { index: 0, value: 0 }
+            ]
+        );
+        assert_eq!(
+            poll_vec(skip.as_mut()),
+            vec![VecDiff::InsertAt { index: 0, value: 4 }]
+        );
+
+        n.set(3);
+        assert_eq!(
+            poll_vec(take_signal.as_mut()),
+            vec![
+                VecDiff::Push { value: 3 },
+                VecDiff::RemoveAt { index: 0 },
+                VecDiff::InsertAt { index: 2, value: 4 },
+                VecDiff::RemoveAt { index: 2 },
+                VecDiff::InsertAt { index: 0, value: 0 }
+            ]
+        );
+    }
+}